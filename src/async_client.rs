@@ -0,0 +1,314 @@
+//! The asynchronous client for interacting with the V3 API, available with the `async` feature.
+//!
+//! The client isn't tied to any particular `async` runtime: requests go through an [AsyncTransport],
+//! which can be backed by whichever HTTP stack the caller's runtime uses.
+//! By default, [ThreadedTransport] runs a blocking [Transport] on a background thread per request.
+
+use std::{
+    fmt::Debug,
+    future::Future,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{Context, Poll, Waker},
+    thread,
+    time::Instant,
+};
+
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+use super::*;
+
+/// Boxed future returned by an [AsyncTransport].
+pub type TransportFuture<'a> = Pin<Box<dyn Future<Output = Result<HttpResponse, ClientError>> + Send + 'a>>;
+
+/// Something that can asynchronously send HTTP requests on behalf of an [AsyncClient].
+///
+/// Non-2xx responses should be returned as an [HttpResponse] rather than an error;
+/// errors are reserved for requests that couldn't be completed at all.
+pub trait AsyncTransport: Debug + Send + Sync {
+    /// Send a request and return a future of its response.
+    ///
+    /// # Arguments
+    ///
+    /// * `request` - the request to send
+    fn send<'a>(&'a self, request: &'a HttpRequest) -> TransportFuture<'a>;
+}
+
+/// Runtime-agnostic [AsyncTransport] that sends each request through a blocking [Transport] on a background thread.
+#[derive(Debug, Clone)]
+pub struct ThreadedTransport {
+    /// The blocking transport.
+    transport: Arc<dyn Transport>,
+}
+
+impl ThreadedTransport {
+    /// Create a new [ThreadedTransport].
+    ///
+    /// # Arguments
+    ///
+    /// * `transport` - the blocking transport to send requests through
+    pub fn new<T: Transport + 'static>(transport: T) -> Self {
+        Self {
+            transport: Arc::new(transport),
+        }
+    }
+}
+
+impl Default for ThreadedTransport {
+    fn default() -> Self {
+        Self::new(UreqTransport::new())
+    }
+}
+
+impl AsyncTransport for ThreadedTransport {
+    fn send<'a>(&'a self, request: &'a HttpRequest) -> TransportFuture<'a> {
+        let state = Arc::new(Mutex::new(ThreadedState::default()));
+        let thread_state = state.clone();
+        let transport = self.transport.clone();
+        let request = request.clone();
+        thread::spawn(move || {
+            let result = transport.send(&request);
+            let mut state = thread_state.lock().unwrap_or_else(|e| e.into_inner());
+            state.result = Some(result);
+            if let Some(waker) = state.waker.take() {
+                waker.wake();
+            }
+        });
+        Box::pin(ThreadedFuture { state })
+    }
+}
+
+/// State shared between a [ThreadedFuture] and its background thread.
+#[derive(Debug, Default)]
+struct ThreadedState {
+    /// Result of the request, once it has finished.
+    result: Option<Result<HttpResponse, ClientError>>,
+    /// Waker of the task awaiting the result.
+    waker: Option<Waker>,
+}
+
+/// Future that completes once a background thread has finished a request.
+#[derive(Debug)]
+struct ThreadedFuture {
+    /// State shared with the background thread.
+    state: Arc<Mutex<ThreadedState>>,
+}
+
+impl Future for ThreadedFuture {
+    type Output = Result<HttpResponse, ClientError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        match state.result.take() {
+            Some(result) => Poll::Ready(result),
+            None => {
+                state.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+/// Asynchronous client for interacting with the MBTA V3 API.
+///
+/// Exposes the same endpoints as [Client], returning the same [Response] and [ClientError] types.
+///
+/// ```
+/// # use std::env;
+/// use mbta_rs::AsyncClient;
+///
+/// # let client = match env::var("MBTA_TOKEN") {
+/// #     Ok(token) => AsyncClient::with_key(token),
+/// #     Err(_) => AsyncClient::without_key()
+/// # };
+/// async fn print_alerts(client: &AsyncClient) {
+///     if let Ok(response) = client.alerts(&[("page[limit]", "3")]).await {
+///         for alert in response.data {
+///             println!("MBTA alert: {}", alert.attributes.header);
+///         }
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct AsyncClient {
+    /// Configuration applied to every request, such as the base URL and API key.
+    config: ClientConfig,
+    /// Asynchronous HTTP transport that does all the heavy lifting.
+    transport: Arc<dyn AsyncTransport>,
+}
+
+/// Clients are compared by their configuration; the HTTP transport is not compared.
+impl PartialEq for AsyncClient {
+    fn eq(&self, other: &Self) -> bool {
+        self.config == other.config
+    }
+}
+
+impl AsyncClient {
+    /// Create an [AsyncClient] without an API key.
+    pub fn without_key() -> Self {
+        Self::from_parts(ClientConfig::new(BASE_URL, None), ThreadedTransport::default())
+    }
+
+    /// Create an [AsyncClient] with an API key.
+    ///
+    /// # Arguments
+    ///
+    /// * `api_key` - the API key to use
+    pub fn with_key<S: Into<String>>(api_key: S) -> Self {
+        Self::from_parts(ClientConfig::new(BASE_URL, Some(ApiKey::header(api_key))), ThreadedTransport::default())
+    }
+
+    /// Create an [AsyncClient] with a custom base URL and no API key.
+    /// This method should only be used for mocking/testing purposes.
+    ///
+    /// # Arguments
+    ///
+    /// * `base_url` - the base URL to use
+    pub fn with_url<S: Into<String>>(base_url: S) -> Self {
+        Self::from_parts(ClientConfig::new(base_url, None), ThreadedTransport::default())
+    }
+
+    /// Create an [AsyncClient] from its configuration and transport.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - the configuration applied to every request
+    /// * `transport` - the transport to send requests through
+    pub(crate) fn from_parts<T: AsyncTransport + 'static>(config: ClientConfig, transport: T) -> Self {
+        Self {
+            config,
+            transport: Arc::new(transport),
+        }
+    }
+
+    /// Replace the asynchronous HTTP transport of an [AsyncClient], such as with one backed by the caller's runtime.
+    /// The transport is shared between clones of the returned client.
+    ///
+    /// # Arguments
+    ///
+    /// * `transport` - the transport to send requests through
+    pub fn with_transport<T: AsyncTransport + 'static>(mut self, transport: T) -> Self {
+        self.transport = Arc::new(transport);
+        self
+    }
+
+    /// Returns the status of the API, such as the version of the GTFS feed it is serving.
+    ///
+    /// Asynchronous version of [Client::status].
+    pub async fn status(&self) -> Result<Response<Status>, ClientError> {
+        self.fetch_one::<Status, String, String>("", &[]).await
+    }
+
+    /// Returns the resources of any endpoint with multiple return objects.
+    ///
+    /// Asynchronous version of [Client::fetch].
+    ///
+    /// # Arguments
+    ///
+    /// * `query_params` - a slice of pairings of query parameter names to values, limited to [Endpoint::ALLOWED_QUERY_PARAMS]
+    pub async fn fetch<E: MultipleEndpoint, K: AsRef<str>, V: AsRef<str>>(
+        &self,
+        query_params: &[(K, V)],
+    ) -> Result<Response<E::Model>, ClientError> {
+        validate_query_params(E::ALLOWED_QUERY_PARAMS, query_params)?;
+        self.get(E::PATH, query_params).await
+    }
+
+    /// Returns the resource of any endpoint with single return objects given its id.
+    ///
+    /// Asynchronous version of [Client::fetch_one].
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - the id of the resource to return
+    /// * `query_params` - a slice of pairings of query parameter names to values, limited to [Endpoint::ALLOWED_QUERY_PARAMS]
+    pub async fn fetch_one<E: SingleEndpoint, K: AsRef<str>, V: AsRef<str>>(
+        &self,
+        id: &str,
+        query_params: &[(K, V)],
+    ) -> Result<Response<E::Model>, ClientError> {
+        validate_query_params(E::ALLOWED_QUERY_PARAMS, query_params)?;
+        self.get(&E::path(id), query_params).await
+    }
+
+    /// Returns the JSON body of a `GET` request to any path with any query parameters.
+    ///
+    /// Asynchronous version of [Client::get_raw].
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - the endpoint path, relative to the base URL, such as `"stops/place-sstat"`
+    /// * `query_params` - a slice of pairings of query parameter names to values
+    pub async fn get_raw<K: AsRef<str>, V: AsRef<str>>(&self, path: &str, query_params: &[(K, V)]) -> Result<Value, ClientError> {
+        self.get_as(path, query_params).await
+    }
+
+    /// Returns the body of a `GET` request to any path with any query parameters, deserialized as any type.
+    ///
+    /// Asynchronous version of [Client::get_as].
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - the endpoint path, relative to the base URL, such as `"stops/place-sstat"`
+    /// * `query_params` - a slice of pairings of query parameter names to values
+    pub async fn get_as<T: DeserializeOwned, K: AsRef<str>, V: AsRef<str>>(
+        &self,
+        path: &str,
+        query_params: &[(K, V)],
+    ) -> Result<T, ClientError> {
+        let request = self.config.request(path.trim_start_matches('/'), query_params);
+        let response = self.transport.send(&request).await?;
+        if !response.is_success() {
+            return Err(ClientError::from_response(&response));
+        }
+        deserialize(&request.url, &response.body)
+    }
+
+    /// Helper method for making generalized `GET` requests to any endpoint with any query parameters.
+    /// Presumes that all query parameters given are valid.
+    ///
+    /// # Arguments
+    ///
+    /// * query_params - a slice of pairings of query parameter names to values
+    pub(crate) async fn get<T: DeserializeOwned, K: AsRef<str>, V: AsRef<str>>(
+        &self,
+        endpoint: &str,
+        query_params: &[(K, V)],
+    ) -> Result<Response<T>, ClientError> {
+        let started = Instant::now();
+        let request = self.config.request(endpoint, query_params);
+        let response = self.transport.send(&request).await?;
+        parse_response(&request, &response, started, false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use rstest::*;
+
+    #[rstest]
+    fn test_async_client_constructors() {
+        // Arrange
+        let expected = [
+            ClientConfig::new("https://api-v3.mbta.com", None),
+            ClientConfig::new("https://api-v3.mbta.com", Some(ApiKey::header("test key"))),
+            ClientConfig::new("https://foobar.com", None),
+        ];
+
+        // Act
+        let actual = [
+            AsyncClient::without_key(),
+            AsyncClient::with_key("test key"),
+            AsyncClient::with_url("https://foobar.com"),
+        ];
+
+        // Assert
+        for (client, config) in actual.iter().zip(expected) {
+            assert_eq!(client.config, config);
+        }
+    }
+}
@@ -0,0 +1,490 @@
+//! Pluggable caching of responses, for data that rarely changes such as routes, stops, and shapes.
+
+use std::{
+    collections::HashMap,
+    fmt::Debug,
+    fs,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex, MutexGuard},
+    time::{Duration, SystemTime},
+};
+
+use serde::{Deserialize, Serialize};
+
+use super::*;
+
+/// Endpoints whose data changes at most daily, as cached by [CachePolicy::static_data].
+pub const STATIC_ENDPOINTS: [&str; 8] = [
+    "facilities",
+    "lines",
+    "route_patterns",
+    "routes",
+    "services",
+    "shapes",
+    "stops",
+    "trips",
+];
+
+/// Successful response body stored in a [ResponseCache].
+#[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize)]
+pub struct CachedResponse {
+    /// Response body.
+    pub body: String,
+    /// When the response was stored.
+    pub stored_at: SystemTime,
+}
+
+/// Storage for cached responses, keyed by endpoint plus normalized query parameters.
+/// Freshness is decided by the [Client]'s [CachePolicy], so caches just store and return entries.
+pub trait ResponseCache: Debug + Send + Sync {
+    /// Returns the cached response for a key, if any.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - the cache key
+    fn get(&self, key: &str) -> Option<CachedResponse>;
+
+    /// Store a response for a key, replacing any previous one.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - the cache key
+    /// * `response` - the response to store
+    fn put(&self, key: &str, response: CachedResponse);
+
+    /// Remove every cached response.
+    fn clear(&self);
+}
+
+/// A shared cache, such as one [FileCache] used by several clients.
+impl<C: ResponseCache + ?Sized> ResponseCache for Arc<C> {
+    fn get(&self, key: &str) -> Option<CachedResponse> {
+        (**self).get(key)
+    }
+
+    fn put(&self, key: &str, response: CachedResponse) {
+        (**self).put(key, response)
+    }
+
+    fn clear(&self) {
+        (**self).clear()
+    }
+}
+
+/// How long responses from each endpoint stay fresh in a [ResponseCache].
+/// Endpoints without a time to live aren't cached.
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct CachePolicy {
+    /// Time to live by endpoint name.
+    ttls: HashMap<String, Duration>,
+    /// Time to live for endpoints without their own.
+    default_ttl: Option<Duration>,
+}
+
+impl CachePolicy {
+    /// Create a [CachePolicy] that doesn't cache any endpoint.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a [CachePolicy] that caches every endpoint in [STATIC_ENDPOINTS] for the same amount of time.
+    ///
+    /// # Arguments
+    ///
+    /// * `ttl` - the time to live
+    pub fn static_data(ttl: Duration) -> Self {
+        STATIC_ENDPOINTS.iter().fold(Self::new(), |policy, endpoint| policy.with_ttl(*endpoint, ttl))
+    }
+
+    /// Set the time to live of an endpoint, covering both its plural and single forms.
+    ///
+    /// # Arguments
+    ///
+    /// * `endpoint` - the endpoint name, such as `"routes"`
+    /// * `ttl` - the time to live
+    pub fn with_ttl<S: Into<String>>(mut self, endpoint: S, ttl: Duration) -> Self {
+        self.ttls.insert(endpoint.into(), ttl);
+        self
+    }
+
+    /// Set the time to live of every endpoint without its own.
+    ///
+    /// # Arguments
+    ///
+    /// * `ttl` - the time to live
+    pub fn with_default_ttl(mut self, ttl: Duration) -> Self {
+        self.default_ttl = Some(ttl);
+        self
+    }
+
+    /// Time to live of an endpoint, [None] if it isn't cached.
+    ///
+    /// # Arguments
+    ///
+    /// * `endpoint` - the endpoint path, such as `"routes"` or `"routes/Red"`
+    pub fn ttl(&self, endpoint: &str) -> Option<Duration> {
+        let name = endpoint.split('/').next().unwrap_or(endpoint);
+        self.ttls.get(name).copied().or(self.default_ttl)
+    }
+
+    /// Returns whether a cached response is still fresh for an endpoint.
+    ///
+    /// # Arguments
+    ///
+    /// * `endpoint` - the endpoint path
+    /// * `response` - the cached response
+    /// * `now` - the current time
+    pub fn is_fresh(&self, endpoint: &str, response: &CachedResponse, now: SystemTime) -> bool {
+        match (self.ttl(endpoint), now.duration_since(response.stored_at)) {
+            (Some(ttl), Ok(age)) => age < ttl,
+            _ => false,
+        }
+    }
+}
+
+/// In-memory [ResponseCache] that evicts the least recently used response once full.
+#[derive(Debug)]
+pub struct MemoryCache {
+    /// Maximum number of responses.
+    capacity: usize,
+    /// Responses by key, along with when each was last used.
+    entries: Mutex<LruEntries<CachedResponse>>,
+}
+
+/// Entries of an in-memory cache that evicts the least recently used entry once full.
+#[derive(Debug)]
+pub(crate) struct LruEntries<V> {
+    /// Values by key, along with the tick each was last used at.
+    values: HashMap<String, (V, u64)>,
+    /// Counter that increases on every use.
+    tick: u64,
+}
+
+impl<V> LruEntries<V> {
+    /// The value for a key, if there is one, marking it as used.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - the key
+    pub(crate) fn get(&mut self, key: &str) -> Option<&V> {
+        self.tick += 1;
+        let (value, last_used) = self.values.get_mut(key)?;
+        *last_used = self.tick;
+        Some(value)
+    }
+
+    /// Store the value for a key, evicting the least recently used entry first if there are already `capacity` others.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - the key
+    /// * `value` - the value
+    /// * `capacity` - the maximum number of entries
+    pub(crate) fn put(&mut self, key: &str, value: V, capacity: usize) {
+        self.tick += 1;
+        if !self.values.contains_key(key) && self.values.len() >= capacity {
+            let least_recent = self.values.iter().min_by_key(|(_, (_, last_used))| *last_used).map(|(k, _)| k.clone());
+            if let Some(least_recent) = least_recent {
+                self.values.remove(&least_recent);
+            }
+        }
+        self.values.insert(key.into(), (value, self.tick));
+    }
+
+    /// Number of entries.
+    pub(crate) fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Remove all entries.
+    pub(crate) fn clear(&mut self) {
+        self.values.clear();
+    }
+}
+
+impl<V> Default for LruEntries<V> {
+    fn default() -> Self {
+        Self {
+            values: HashMap::new(),
+            tick: 0,
+        }
+    }
+}
+
+impl MemoryCache {
+    /// Create an empty [MemoryCache].
+    ///
+    /// # Arguments
+    ///
+    /// * `capacity` - the maximum number of responses to keep, at least 1
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            entries: Mutex::new(LruEntries::default()),
+        }
+    }
+
+    /// Number of cached responses.
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    /// Returns whether there are no cached responses.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Lock the entries, recovering from a poisoned lock since the entries are always left consistent.
+    fn lock(&self) -> MutexGuard<'_, LruEntries<CachedResponse>> {
+        self.entries.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl ResponseCache for MemoryCache {
+    fn get(&self, key: &str) -> Option<CachedResponse> {
+        self.lock().get(key).cloned()
+    }
+
+    fn put(&self, key: &str, response: CachedResponse) {
+        self.lock().put(key, response, self.capacity);
+    }
+
+    fn clear(&self) {
+        self.lock().clear();
+    }
+}
+
+/// File-system [ResponseCache] that keeps each response in its own file, so it survives restarts.
+///
+/// Files are named `mbta-cache-<hash>.json` after a hash of the key and are written atomically;
+/// a file that can't be read or parsed is treated as missing.
+/// Only files named that way are touched, so the directory can be shared with other files.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileCache {
+    /// Directory holding the cache files.
+    dir: PathBuf,
+}
+
+/// Contents of a [FileCache] file.
+#[derive(Debug, Deserialize, Serialize)]
+struct FileEntry {
+    /// The full cache key, to guard against hash collisions.
+    key: String,
+    /// The cached response.
+    response: CachedResponse,
+}
+
+impl FileCache {
+    /// Create a [FileCache] in a directory, creating the directory if needed.
+    ///
+    /// # Arguments
+    ///
+    /// * `dir` - the directory to keep cache files in
+    pub fn new<P: AsRef<Path>>(dir: P) -> Result<Self, ClientError> {
+        fs::create_dir_all(&dir)?;
+        Ok(Self {
+            dir: dir.as_ref().to_path_buf(),
+        })
+    }
+
+    /// Directory holding the cache files.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Path of the file for a key.
+    fn path(&self, key: &str) -> PathBuf {
+        self.dir.join(format!("{}{:016x}.json", FILE_PREFIX, fnv1a(key.as_bytes())))
+    }
+}
+
+/// Prefix of the name of every [FileCache] file.
+const FILE_PREFIX: &str = "mbta-cache-";
+
+/// Returns whether a file name is that of a [FileCache] file, i.e. `mbta-cache-` followed by a 16 digit hash and `.json`.
+///
+/// # Arguments
+///
+/// * `name` - the file name
+fn is_cache_file(name: &str) -> bool {
+    match name.strip_prefix(FILE_PREFIX).and_then(|name| name.strip_suffix(".json")) {
+        Some(hash) => hash.len() == 16 && hash.chars().all(|c| c.is_ascii_hexdigit()),
+        None => false,
+    }
+}
+
+impl ResponseCache for FileCache {
+    fn get(&self, key: &str) -> Option<CachedResponse> {
+        let contents = fs::read_to_string(self.path(key)).ok()?;
+        let entry: FileEntry = serde_json::from_str(&contents).ok()?;
+        (entry.key == key).then_some(entry.response)
+    }
+
+    fn put(&self, key: &str, response: CachedResponse) {
+        let entry = FileEntry { key: key.into(), response };
+        let contents = match serde_json::to_string(&entry) {
+            Ok(contents) => contents,
+            Err(_) => return,
+        };
+        let path = self.path(key);
+        let temp = path.with_extension(format!("{}.tmp", std::process::id()));
+        if fs::write(&temp, contents).and_then(|_| fs::rename(&temp, &path)).is_err() {
+            let _ = fs::remove_file(&temp);
+        }
+    }
+
+    fn clear(&self) {
+        let entries = match fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(_) => return,
+        };
+        for entry in entries.flatten() {
+            if entry.file_name().to_str().is_some_and(is_cache_file) {
+                let _ = fs::remove_file(entry.path());
+            }
+        }
+    }
+}
+
+/// 64-bit FNV-1a hash, which is stable across runs and platforms unlike the standard library's hasher.
+///
+/// # Arguments
+///
+/// * `bytes` - the bytes to hash
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes
+        .iter()
+        .fold(0xcbf29ce484222325, |hash, byte| (hash ^ u64::from(*byte)).wrapping_mul(0x100000001b3))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use rstest::*;
+
+    fn response(body: &str) -> CachedResponse {
+        CachedResponse {
+            body: body.into(),
+            stored_at: SystemTime::UNIX_EPOCH + Duration::from_secs(1_000),
+        }
+    }
+
+    #[rstest]
+    #[case::plural("routes", Some(Duration::from_secs(60)))]
+    #[case::single("routes/Red", Some(Duration::from_secs(60)))]
+    #[case::default("stops", Some(Duration::from_secs(5)))]
+    fn test_cache_policy_ttl(#[case] endpoint: &str, #[case] expected: Option<Duration>) {
+        // Arrange
+        let policy = CachePolicy::new()
+            .with_ttl("routes", Duration::from_secs(60))
+            .with_default_ttl(Duration::from_secs(5));
+
+        // Act
+        let actual = policy.ttl(endpoint);
+
+        // Assert
+        assert_eq!(actual, expected);
+    }
+
+    #[rstest]
+    #[case::fresh("shapes", 1_030, true)]
+    #[case::expired("shapes", 1_100, false)]
+    #[case::future("shapes", 900, false)]
+    #[case::uncached("vehicles", 1_030, false)]
+    fn test_cache_policy_is_fresh(#[case] endpoint: &str, #[case] now: u64, #[case] expected: bool) {
+        // Arrange
+        let policy = CachePolicy::static_data(Duration::from_secs(60));
+
+        // Act
+        let actual = policy.is_fresh(endpoint, &response("{}"), SystemTime::UNIX_EPOCH + Duration::from_secs(now));
+
+        // Assert
+        assert_eq!(actual, expected);
+    }
+
+    #[rstest]
+    fn test_memory_cache_evicts_least_recently_used() {
+        // Arrange
+        let cache = MemoryCache::new(2);
+        cache.put("a", response("a"));
+        cache.put("b", response("b"));
+
+        // Act
+        cache.get("a");
+        cache.put("c", response("c"));
+
+        // Assert
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.get("a"), Some(response("a")));
+        assert_eq!(cache.get("b"), None);
+        assert_eq!(cache.get("c"), Some(response("c")));
+        cache.clear();
+        assert!(cache.is_empty());
+    }
+
+    #[rstest]
+    fn test_file_cache_survives_restart() {
+        // Arrange
+        let dir = std::env::temp_dir().join(format!("mbta-rs-file-cache-{}", std::process::id()));
+        let cache = FileCache::new(&dir).expect("failed to create cache");
+
+        // Act
+        cache.put("https://api-v3.mbta.com/routes?", response("routes"));
+        let reopened = FileCache::new(&dir).expect("failed to reopen cache");
+
+        // Assert
+        assert_eq!(reopened.get("https://api-v3.mbta.com/routes?"), Some(response("routes")));
+        assert_eq!(reopened.get("https://api-v3.mbta.com/stops?"), None);
+        reopened.clear();
+        assert_eq!(cache.get("https://api-v3.mbta.com/routes?"), None);
+        fs::remove_dir_all(dir).expect("failed to remove cache");
+    }
+
+    #[rstest]
+    fn test_file_cache_clear_keeps_other_files() {
+        // Arrange
+        let dir = std::env::temp_dir().join(format!("mbta-rs-file-cache-clear-{}", std::process::id()));
+        let cache = FileCache::new(&dir).expect("failed to create cache");
+        let other = dir.join("settings.json");
+        fs::write(&other, "{}").expect("failed to write other file");
+        cache.put("https://api-v3.mbta.com/routes?", response("routes"));
+
+        // Act
+        cache.clear();
+
+        // Assert
+        assert_eq!(cache.get("https://api-v3.mbta.com/routes?"), None);
+        assert_eq!(fs::read_to_string(&other).ok().as_deref(), Some("{}"));
+        fs::remove_dir_all(dir).expect("failed to remove cache");
+    }
+
+    #[rstest]
+    #[case::cache_file("mbta-cache-cbf29ce484222325.json", true)]
+    #[case::other_json("cbf29ce484222325.json", false)]
+    #[case::short_hash("mbta-cache-cbf29ce4.json", false)]
+    #[case::not_hex("mbta-cache-settingssettings.json", false)]
+    #[case::temp_file("mbta-cache-cbf29ce484222325.123.tmp", false)]
+    fn test_is_cache_file(#[case] input: &str, #[case] expected: bool) {
+        // Arrange
+
+        // Act
+        let actual = is_cache_file(input);
+
+        // Assert
+        assert_eq!(actual, expected);
+    }
+
+    #[rstest]
+    #[case::empty("", 0xcbf29ce484222325)]
+    #[case::letter("a", 0xaf63dc4c8601ec8c)]
+    fn test_fnv1a(#[case] input: &str, #[case] expected: u64) {
+        // Arrange
+
+        // Act
+        let actual = fnv1a(input.as_bytes());
+
+        // Assert
+        assert_eq!(actual, expected);
+    }
+}
@@ -1,8 +1,16 @@
 //! The client for interacting with the V3 API.
 
-use std::collections::HashSet;
+use std::{
+    collections::HashSet,
+    sync::{Arc, Mutex},
+    thread,
+    time::{Instant, SystemTime},
+};
+
+use chrono::Utc;
 
 use serde::de::DeserializeOwned;
+use serde_json::Value;
 
 use super::*;
 
@@ -13,7 +21,15 @@ pub const BASE_URL: &str = "https://api-v3.mbta.com";
 #[doc(hidden)]
 #[macro_export]
 macro_rules! mbta_endpoint_multiple {
-    (model=$model:ident, func=$func:ident, allowed_query_params=$allowed_query_params:expr) => {
+    (model=$model:ident, func=$func:ident, pages_func=$pages_func:ident, allowed_query_params=$allowed_query_params:expr) => {
+        impl Endpoint for $model {
+            type Model = Self;
+            const PATH: &'static str = stringify!($func);
+            const ALLOWED_QUERY_PARAMS: &'static [&'static str] = &$allowed_query_params;
+        }
+
+        impl MultipleEndpoint for $model {}
+
         impl Client {
             #[doc = concat!("Returns ", stringify!($func), " in the MBTA system.")]
             ///
@@ -49,16 +65,59 @@ macro_rules! mbta_endpoint_multiple {
             /// }
             /// ```
             pub fn $func<K: AsRef<str>, V: AsRef<str>>(&self, query_params: &[(K, V)]) -> Result<Response<$model>, ClientError> {
-                let allowed_query_params: HashSet<String> = $allowed_query_params.into_iter().map(|s: &str| s.to_string()).collect();
-                for (k, v) in query_params {
-                    if !allowed_query_params.contains(k.as_ref()) {
-                        return Err(ClientError::InvalidQueryParam {
-                            name: k.as_ref().to_string(),
-                            value: v.as_ref().to_string(),
-                        });
-                    }
-                }
-                self.get(stringify!($func), query_params)
+                self.fetch::<$model, K, V>(query_params)
+            }
+
+            #[doc = concat!("Returns a lazy iterator over the pages of ", stringify!($func), " in the MBTA system, following the `next` link of each page.")]
+            ///
+            /// Each page is only requested once the iterator reaches it, and iteration starts from `page[offset]` if it is given.
+            /// Pages are only split up by the API if `page[limit]` is given.
+            /// The allowed query parameters are the same as the non-paginated endpoint.
+            ///
+            /// # Allowed Query Parameters
+            ///
+            #[doc = concat!("`", stringify!($allowed_query_params), "`")]
+            ///
+            /// # Arguments
+            ///
+            /// * `query_params` - a slice of pairings of query parameter names to values
+            ///
+            /// ```
+            /// # use std::env;
+            /// # use mbta_rs::Client;
+            /// #
+            /// # let client = match env::var("MBTA_TOKEN") {
+            /// #     Ok(token) => Client::with_key(token),
+            /// #     Err(_) => Client::without_key()
+            /// # };
+            /// #
+            /// # let query_params = [
+            /// #     ("page[limit]", "3")
+            /// # ];
+            #[doc = concat!("if let Ok(pages) = client.", stringify!($pages_func), "(&query_params) {\n")]
+            ///     for item in pages.items().take(6).flatten() {
+            ///         println!("{}", item.id);
+            ///     }
+            /// }
+            /// ```
+            pub fn $pages_func<K: AsRef<str>, V: AsRef<str>>(&self, query_params: &[(K, V)]) -> Result<Pages<'_, $model>, ClientError> {
+                self.fetch_pages::<$model, K, V>(query_params)
+            }
+        }
+
+        #[cfg(feature = "async")]
+        impl AsyncClient {
+            #[doc = concat!("Returns ", stringify!($func), " in the MBTA system.")]
+            ///
+            #[doc = concat!("Asynchronous version of [Client::", stringify!($func), "], with the same allowed query parameters:")]
+            ///
+            #[doc = concat!("`", stringify!($allowed_query_params), "`")]
+            ///
+            /// # Arguments
+            ///
+            /// * `query_params` - a slice of pairings of query parameter names to values
+            pub async fn $func<K: AsRef<str>, V: AsRef<str>>(&self, query_params: &[(K, V)]) -> Result<Response<$model>, ClientError> {
+                self.fetch::<$model, K, V>(query_params).await
             }
         }
     };
@@ -68,7 +127,15 @@ macro_rules! mbta_endpoint_multiple {
 #[doc(hidden)]
 #[macro_export]
 macro_rules! mbta_endpoint_single {
-    (model=$model:ident, func=$func:ident, endpoint=$endpoint:expr, allowed_query_params=$allowed_query_params:expr) => {
+    (model=$model:ident, id=$id:ident, func=$func:ident, func_with_params=$func_with_params:ident, endpoint=$endpoint:expr, allowed_query_params=$allowed_query_params:expr) => {
+        impl Endpoint for $model {
+            type Model = Self;
+            const PATH: &'static str = $endpoint;
+            const ALLOWED_QUERY_PARAMS: &'static [&'static str] = &$allowed_query_params;
+        }
+
+        impl SingleEndpoint for $model {}
+
         impl Client {
             #[doc = concat!("Returns a ", stringify!($func), " in the MBTA system given its id.")]
             ///
@@ -90,8 +157,112 @@ macro_rules! mbta_endpoint_single {
             ///     println!("{}", item.data.id);
             /// }
             /// ```
-            pub fn $func(&self, id: &str) -> Result<Response<$model>, ClientError> {
-                self.get::<$model, String, String>(&format!("{}/{}", $endpoint, id), &[])
+            pub fn $func(&self, id: impl Into<$id>) -> Result<Response<$model>, ClientError> {
+                self.$func_with_params::<String, String>(id, &[])
+            }
+
+            #[doc = concat!("Returns a ", stringify!($func), " in the MBTA system given its id, along with extra query parameters.")]
+            ///
+            /// The request will fail if you include any query parameters that are *not* the ones specified below.
+            ///
+            /// # Allowed Query Parameters
+            ///
+            #[doc = concat!("`", stringify!($allowed_query_params), "`")]
+            ///
+            /// # Arguments
+            #[doc = concat!("* `id` - the id of the ", stringify!($func), " to return")]
+            /// * `query_params` - a slice of pairings of query parameter names to values
+            pub fn $func_with_params<K: AsRef<str>, V: AsRef<str>>(
+                &self,
+                id: impl Into<$id>,
+                query_params: &[(K, V)],
+            ) -> Result<Response<$model>, ClientError> {
+                self.fetch_one::<$model, K, V>(id.into().as_str(), query_params)
+            }
+        }
+
+        #[cfg(feature = "async")]
+        impl AsyncClient {
+            #[doc = concat!("Returns a ", stringify!($func), " in the MBTA system given its id.")]
+            ///
+            #[doc = concat!("Asynchronous version of [Client::", stringify!($func), "].")]
+            ///
+            /// # Arguments
+            #[doc = concat!("* `id` - the id of the ", stringify!($func), " to return")]
+            pub async fn $func(&self, id: impl Into<$id>) -> Result<Response<$model>, ClientError> {
+                self.$func_with_params::<String, String>(id, &[]).await
+            }
+
+            #[doc = concat!("Returns a ", stringify!($func), " in the MBTA system given its id, along with extra query parameters.")]
+            ///
+            #[doc = concat!("Asynchronous version of [Client::", stringify!($func_with_params), "], with the same allowed query parameters:")]
+            ///
+            #[doc = concat!("`", stringify!($allowed_query_params), "`")]
+            ///
+            /// # Arguments
+            #[doc = concat!("* `id` - the id of the ", stringify!($func), " to return")]
+            /// * `query_params` - a slice of pairings of query parameter names to values
+            pub async fn $func_with_params<K: AsRef<str>, V: AsRef<str>>(
+                &self,
+                id: impl Into<$id>,
+                query_params: &[(K, V)],
+            ) -> Result<Response<$model>, ClientError> {
+                self.fetch_one::<$model, K, V>(id.into().as_str(), query_params).await
+            }
+        }
+    };
+}
+
+/// Attribute macro for quickly implementing MBTA client endpoints that stream server-sent events.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! mbta_endpoint_stream {
+    (model=$model:ident, func=$func:ident, endpoint=$endpoint:expr, allowed_query_params=$allowed_query_params:expr) => {
+        impl StreamEndpoint for Vec<$model> {
+            type Item = $model;
+            const STREAM_QUERY_PARAMS: &'static [&'static str] = &$allowed_query_params;
+        }
+
+        impl Client {
+            #[doc = concat!("Returns a stream of events as ", $endpoint, " in the MBTA system are reset, added, updated, or removed.")]
+            ///
+            /// The stream connects lazily, reconnects whenever the connection drops, and yields keep-alives while nothing changes.
+            /// The request will fail if you include any query parameters that are *not* the ones specified below;
+            /// pagination, sorting, and included resources aren't supported when streaming.
+            ///
+            /// # Allowed Query Parameters
+            ///
+            #[doc = concat!("`", stringify!($allowed_query_params), "`")]
+            ///
+            /// # Arguments
+            ///
+            /// * `query_params` - a slice of pairings of query parameter names to values
+            ///
+            /// ```no_run
+            /// # use std::env;
+            /// # use mbta_rs::*;
+            /// #
+            /// # let client = match env::var("MBTA_TOKEN") {
+            /// #     Ok(token) => Client::with_key(token),
+            /// #     Err(_) => Client::without_key()
+            /// # };
+            /// #
+            /// # let query_params = [
+            /// #     ("filter[route]", "Red")
+            /// # ];
+            #[doc = concat!("if let Ok(stream) = client.", stringify!($func), "(&query_params) {\n")]
+            ///     for event in stream.flatten() {
+            ///         match event {
+            ///             Event::Reset(items) => println!("{} items", items.len()),
+            ///             Event::Add(item) | Event::Update(item) => println!("{}", item.id),
+            ///             Event::Remove(removed) => println!("{} removed", removed.id),
+            ///             Event::KeepAlive => {}
+            ///         }
+            ///     }
+            /// }
+            /// ```
+            pub fn $func<K: AsRef<str>, V: AsRef<str>>(&self, query_params: &[(K, V)]) -> Result<EventStream<'_, $model>, ClientError> {
+                self.stream::<Vec<$model>, K, V>(query_params)
             }
         }
     };
@@ -100,10 +271,12 @@ macro_rules! mbta_endpoint_single {
 mbta_endpoint_multiple!(
     model = Alerts,
     func = alerts,
+    pages_func = alerts_pages,
     allowed_query_params = [
         "page[offset]",
         "page[limit]",
         "sort",
+        "include",
         "filter[activity]",
         "filter[route_type]",
         "filter[direction_id]",
@@ -121,25 +294,30 @@ mbta_endpoint_multiple!(
 mbta_endpoint_multiple!(
     model = Facilities,
     func = facilities,
-    allowed_query_params = ["page[offset]", "page[limit]", "sort", "filter[stop]", "filter[type]",]
+    pages_func = facilities_pages,
+    allowed_query_params = ["page[offset]", "page[limit]", "sort", "include", "filter[stop]", "filter[type]",]
 );
 mbta_endpoint_multiple!(
     model = Lines,
     func = lines,
-    allowed_query_params = ["page[offset]", "page[limit]", "sort", "filter[id]",]
+    pages_func = lines_pages,
+    allowed_query_params = ["page[offset]", "page[limit]", "sort", "include", "filter[id]",]
 );
 mbta_endpoint_multiple!(
     model = LiveFacilities,
     func = live_facilities,
-    allowed_query_params = ["page[offset]", "page[limit]", "sort", "filter[id]",]
+    pages_func = live_facilities_pages,
+    allowed_query_params = ["page[offset]", "page[limit]", "sort", "include", "filter[id]",]
 );
 mbta_endpoint_multiple!(
     model = Predictions,
     func = predictions,
+    pages_func = predictions_pages,
     allowed_query_params = [
         "page[offset]",
         "page[limit]",
         "sort",
+        "include",
         "filter[latitude]",
         "filter[longitude]",
         "filter[radius]",
@@ -149,15 +327,18 @@ mbta_endpoint_multiple!(
         "filter[route]",
         "filter[trip]",
         "filter[route_pattern]",
+        "filter[revenue]",
     ]
 );
 mbta_endpoint_multiple!(
     model = Routes,
     func = routes,
+    pages_func = routes_pages,
     allowed_query_params = [
         "page[offset]",
         "page[limit]",
         "sort",
+        "include",
         "filter[stop]",
         "filter[type]",
         "filter[direction_id]",
@@ -168,23 +349,29 @@ mbta_endpoint_multiple!(
 mbta_endpoint_multiple!(
     model = RoutePatterns,
     func = route_patterns,
+    pages_func = route_patterns_pages,
     allowed_query_params = [
         "page[offset]",
         "page[limit]",
         "sort",
+        "include",
         "filter[id]",
         "filter[route]",
         "filter[direction_id]",
         "filter[stop]",
+        "filter[canonical]",
+        "filter[date]",
     ]
 );
 mbta_endpoint_multiple!(
     model = Schedules,
     func = schedules,
+    pages_func = schedules_pages,
     allowed_query_params = [
         "page[offset]",
         "page[limit]",
         "sort",
+        "include",
         "filter[date]",
         "filter[direction_id]",
         "filter[route_type]",
@@ -199,20 +386,24 @@ mbta_endpoint_multiple!(
 mbta_endpoint_multiple!(
     model = Services,
     func = services,
-    allowed_query_params = ["page[offset]", "page[limit]", "sort", "filter[id]", "filter[route]",]
+    pages_func = services_pages,
+    allowed_query_params = ["page[offset]", "page[limit]", "sort", "include", "filter[id]", "filter[route]",]
 );
 mbta_endpoint_multiple!(
     model = Shapes,
     func = shapes,
-    allowed_query_params = ["page[offset]", "page[limit]", "sort", "filter[route]",]
+    pages_func = shapes_pages,
+    allowed_query_params = ["page[offset]", "page[limit]", "sort", "include", "filter[route]",]
 );
 mbta_endpoint_multiple!(
     model = Stops,
     func = stops,
+    pages_func = stops_pages,
     allowed_query_params = [
         "page[offset]",
         "page[limit]",
         "sort",
+        "include",
         "filter[date]",
         "filter[direction_id]",
         "filter[latitude]",
@@ -223,57 +414,261 @@ mbta_endpoint_multiple!(
         "filter[route]",
         "filter[service]",
         "filter[location_type]",
+        "filter[parent_station]",
     ]
 );
 mbta_endpoint_multiple!(
     model = Trips,
     func = trips,
+    pages_func = trips_pages,
     allowed_query_params = [
         "page[offset]",
         "page[limit]",
         "sort",
+        "include",
         "filter[date]",
         "filter[direction_id]",
         "filter[route]",
         "filter[route_pattern]",
         "filter[id]",
         "filter[name]",
+        "filter[revenue]",
     ]
 );
 mbta_endpoint_multiple!(
     model = Vehicles,
     func = vehicles,
+    pages_func = vehicles_pages,
     allowed_query_params = [
         "page[offset]",
         "page[limit]",
         "sort",
+        "include",
         "filter[id]",
         "filter[trip]",
         "filter[label]",
         "filter[route]",
         "filter[direction_id]",
         "filter[route_type]",
+        "filter[revenue]",
     ]
 );
 
-mbta_endpoint_single!(model = Alert, func = alert, endpoint = "alerts", allowed_query_params = []);
-mbta_endpoint_single!(model = Facility, func = facility, endpoint = "facilities", allowed_query_params = []);
-mbta_endpoint_single!(model = Line, func = line, endpoint = "lines", allowed_query_params = []);
-mbta_endpoint_single!(model = Route, func = route, endpoint = "routes", allowed_query_params = []);
-mbta_endpoint_single!(model = RoutePattern, func = route_pattern, endpoint = "route_patterns", allowed_query_params = []);
-mbta_endpoint_single!(model = Service, func = service, endpoint = "services", allowed_query_params = []);
-mbta_endpoint_single!(model = Shape, func = shape, endpoint = "shapes", allowed_query_params = []);
-mbta_endpoint_single!(model = Stop, func = stop, endpoint = "stops", allowed_query_params = []);
-mbta_endpoint_single!(model = Trip, func = trip, endpoint = "trips", allowed_query_params = []);
-mbta_endpoint_single!(model = Vehicle, func = vehicle, endpoint = "vehicles", allowed_query_params = []);
+mbta_endpoint_single!(
+    model = Alert,
+    id = AlertId,
+    func = alert,
+    func_with_params = alert_with_params,
+    endpoint = "alerts",
+    allowed_query_params = ["include"]
+);
+mbta_endpoint_single!(
+    model = Facility,
+    id = FacilityId,
+    func = facility,
+    func_with_params = facility_with_params,
+    endpoint = "facilities",
+    allowed_query_params = ["include"]
+);
+mbta_endpoint_single!(
+    model = Line,
+    id = LineId,
+    func = line,
+    func_with_params = line_with_params,
+    endpoint = "lines",
+    allowed_query_params = ["include"]
+);
+mbta_endpoint_single!(
+    model = LiveFacility,
+    id = FacilityId,
+    func = live_facility,
+    func_with_params = live_facility_with_params,
+    endpoint = "live_facilities",
+    allowed_query_params = ["include"]
+);
+mbta_endpoint_single!(
+    model = Route,
+    id = RouteId,
+    func = route,
+    func_with_params = route_with_params,
+    endpoint = "routes",
+    allowed_query_params = ["include"]
+);
+mbta_endpoint_single!(
+    model = RoutePattern,
+    id = RoutePatternId,
+    func = route_pattern,
+    func_with_params = route_pattern_with_params,
+    endpoint = "route_patterns",
+    allowed_query_params = ["include"]
+);
+mbta_endpoint_single!(
+    model = Service,
+    id = ServiceId,
+    func = service,
+    func_with_params = service_with_params,
+    endpoint = "services",
+    allowed_query_params = ["include"]
+);
+mbta_endpoint_single!(
+    model = Shape,
+    id = ShapeId,
+    func = shape,
+    func_with_params = shape_with_params,
+    endpoint = "shapes",
+    allowed_query_params = ["include"]
+);
+mbta_endpoint_single!(
+    model = Stop,
+    id = StopId,
+    func = stop,
+    func_with_params = stop_with_params,
+    endpoint = "stops",
+    allowed_query_params = ["include"]
+);
+mbta_endpoint_single!(
+    model = Trip,
+    id = TripId,
+    func = trip,
+    func_with_params = trip_with_params,
+    endpoint = "trips",
+    allowed_query_params = ["include"]
+);
+mbta_endpoint_single!(
+    model = Vehicle,
+    id = VehicleId,
+    func = vehicle,
+    func_with_params = vehicle_with_params,
+    endpoint = "vehicles",
+    allowed_query_params = ["include"]
+);
+
+impl Endpoint for Status {
+    type Model = Self;
+    const PATH: &'static str = "status";
+    const ALLOWED_QUERY_PARAMS: &'static [&'static str] = &[];
+}
+
+/// The status endpoint has only one resource, so it's requested without an id.
+impl SingleEndpoint for Status {
+    fn path(_id: &str) -> String {
+        Self::PATH.into()
+    }
+}
+
+mbta_endpoint_stream!(
+    model = Alert,
+    func = stream_alerts,
+    endpoint = "alerts",
+    allowed_query_params = [
+        "filter[activity]",
+        "filter[route_type]",
+        "filter[direction_id]",
+        "filter[route]",
+        "filter[stop]",
+        "filter[trip]",
+        "filter[facility]",
+        "filter[id]",
+        "filter[banner]",
+        "filter[datetime]",
+        "filter[lifecycle]",
+        "filter[severity]",
+    ]
+);
+mbta_endpoint_stream!(
+    model = Prediction,
+    func = stream_predictions,
+    endpoint = "predictions",
+    allowed_query_params = [
+        "filter[latitude]",
+        "filter[longitude]",
+        "filter[radius]",
+        "filter[direction_id]",
+        "filter[route_type]",
+        "filter[stop]",
+        "filter[route]",
+        "filter[trip]",
+        "filter[route_pattern]",
+        "filter[revenue]",
+    ]
+);
+mbta_endpoint_stream!(
+    model = Vehicle,
+    func = stream_vehicles,
+    endpoint = "vehicles",
+    allowed_query_params = [
+        "filter[id]",
+        "filter[trip]",
+        "filter[label]",
+        "filter[route]",
+        "filter[direction_id]",
+        "filter[route_type]",
+        "filter[revenue]",
+    ]
+);
+
+/// Helper function for checking that all query parameters given are allowed by an endpoint.
+///
+/// # Arguments
+///
+/// * `allowed_query_params` - the query parameter names allowed by the endpoint
+/// * `query_params` - a slice of pairings of query parameter names to values
+pub(crate) fn validate_query_params<K: AsRef<str>, V: AsRef<str>>(
+    allowed_query_params: &[&str],
+    query_params: &[(K, V)],
+) -> Result<(), ClientError> {
+    let allowed_query_params: HashSet<&str> = allowed_query_params.iter().copied().collect();
+    for (k, v) in query_params {
+        if !allowed_query_params.contains(k.as_ref()) {
+            return Err(ClientError::InvalidQueryParam {
+                name: k.as_ref().to_string(),
+                value: v.as_ref().to_string(),
+            });
+        }
+    }
+    Ok(())
+}
 
 /// Synchronous client for interacting with the MBTA V3 API.
-#[derive(Debug, Clone, PartialEq)]
+///
+/// Each client created by a constructor gets its own pool of keep-alive connections,
+/// which is shared by all of its clones, so clone a client rather than creating a new one for each request or thread.
+#[derive(Debug, Clone)]
 pub struct Client {
-    /// HTTP agent that does all the heavy lifting.
-    api_key: Option<String>,
-    /// API base URL.
-    base_url: String,
+    /// Configuration applied to every request, such as the base URL and API key.
+    config: ClientConfig,
+    /// HTTP transport that does all the heavy lifting.
+    transport: Arc<dyn Transport>,
+    /// What to do to stay under the rate limit.
+    rate_limit_policy: RateLimitPolicy,
+    /// How to retry requests that fail for transient reasons.
+    retry_policy: RetryPolicy,
+    /// Most recent response to each distinct request, if conditional requests are enabled; shared between clones.
+    conditional: Option<Arc<ConditionalCache>>,
+    /// Cache of responses from the endpoints the cache policy covers, shared between clones.
+    cache: Option<Arc<dyn ResponseCache>>,
+    /// How long responses from each endpoint stay fresh in the cache.
+    cache_policy: CachePolicy,
+    /// Whether to drop invalid items from responses instead of failing.
+    lenient: bool,
+    /// Fields the API sent that the data models don't declare, if detection is enabled; shared between clones.
+    schema_drift: Option<Arc<Mutex<SchemaDrift>>>,
+    /// Most recent rate-limit state reported by the API, shared between clones.
+    rate_limit: Arc<Mutex<Option<RateLimit>>>,
+}
+
+/// Clients are compared by their configuration; the HTTP transport is not compared.
+impl PartialEq for Client {
+    fn eq(&self, other: &Self) -> bool {
+        self.config == other.config
+            && self.rate_limit_policy == other.rate_limit_policy
+            && self.retry_policy == other.retry_policy
+            && self.conditional.is_some() == other.conditional.is_some()
+            && self.cache.is_some() == other.cache.is_some()
+            && self.cache_policy == other.cache_policy
+            && self.lenient == other.lenient
+            && self.schema_drift.is_some() == other.schema_drift.is_some()
+    }
 }
 
 impl Client {
@@ -281,10 +676,7 @@ impl Client {
     ///
     /// > "Without an api key in the query string or as a request header, requests will be tracked by IP address and have stricter rate limit." - Massachusetts Bay Transportation Authority
     pub fn without_key() -> Self {
-        Self {
-            api_key: None,
-            base_url: BASE_URL.into(),
-        }
+        Self::from_parts(ClientConfig::new(BASE_URL, None), UreqTransport::new())
     }
 
     /// Create a [Client] with an API key.
@@ -293,46 +685,594 @@ impl Client {
     ///
     /// * `api_key` - the API key to use
     pub fn with_key<S: Into<String>>(api_key: S) -> Self {
-        Self {
-            api_key: Some(api_key.into()),
-            base_url: BASE_URL.into(),
-        }
+        Self::from_parts(ClientConfig::new(BASE_URL, Some(ApiKey::header(api_key))), UreqTransport::new())
     }
 
     /// Create a [Client] with a custom base URL and no API key.
-    /// This method should only be used for mocking/testing purposes.
+    /// This method should only be used for mocking/testing purposes; use a [ClientBuilder] to combine a custom base URL with an API key.
     ///
     /// # Arguments
     ///
     /// * `base_url` - the base URL to use
     pub fn with_url<S: Into<String>>(base_url: S) -> Self {
+        Self::from_parts(ClientConfig::new(base_url, None), UreqTransport::new())
+    }
+
+    /// Create a [ClientBuilder] for configuring a [Client] beyond what the other constructors allow.
+    pub fn builder() -> ClientBuilder {
+        ClientBuilder::new()
+    }
+
+    /// Create a [Client] from its configuration and transport.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - the configuration applied to every request
+    /// * `transport` - the transport to send requests through
+    pub(crate) fn from_parts<T: Transport + 'static>(config: ClientConfig, transport: T) -> Self {
         Self {
-            api_key: None,
-            base_url: base_url.into(),
+            config,
+            transport: Arc::new(transport),
+            rate_limit_policy: RateLimitPolicy::default(),
+            retry_policy: RetryPolicy::default(),
+            conditional: None,
+            cache: None,
+            cache_policy: CachePolicy::default(),
+            lenient: false,
+            schema_drift: None,
+            rate_limit: Arc::new(Mutex::new(None)),
         }
     }
 
+    /// Set what a [Client] does to stay under the rate limit, which is [RateLimitPolicy::Disabled] by default.
+    ///
+    /// # Arguments
+    ///
+    /// * `policy` - the rate-limit policy
+    pub fn with_rate_limit_policy(mut self, policy: RateLimitPolicy) -> Self {
+        self.rate_limit_policy = policy;
+        self
+    }
+
+    /// Set how a [Client] retries requests that fail for transient reasons, which is [RetryPolicy::none] by default.
+    ///
+    /// # Arguments
+    ///
+    /// * `policy` - the retry policy
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use mbta_rs::*;
+    ///
+    /// let client = Client::without_key().with_retry_policy(RetryPolicy::new(3).with_base_delay(Duration::from_millis(500)));
+    /// ```
+    pub fn with_retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry_policy = policy;
+        self
+    }
+
+    /// Set whether a [Client] sends conditional requests, which it doesn't by default.
+    ///
+    /// When enabled, the client remembers the `Last-Modified` time and parsed response of each distinct request
+    /// (by endpoint and query parameters, in any order), and sends it back as `If-Modified-Since` the next time.
+    /// If the API answers `304 Not Modified`, the remembered response is returned with [ResponseMeta::not_modified] set,
+    /// which saves downloading and parsing unchanged data, and counts less against the rate limit.
+    /// The remembered responses are shared between clones of the returned client,
+    /// and the least recently used one is forgotten once [DEFAULT_CONDITIONAL_CACHE_CAPACITY] are remembered.
+    ///
+    /// # Arguments
+    ///
+    /// * `enabled` - whether to send conditional requests
+    pub fn with_conditional_requests(mut self, enabled: bool) -> Self {
+        self.conditional = enabled.then(|| Arc::new(ConditionalCache::new(DEFAULT_CONDITIONAL_CACHE_CAPACITY)));
+        self
+    }
+
+    /// Make a [Client] send conditional requests while remembering at most a given number of responses,
+    /// evicting the least recently used one once full; see [Client::with_conditional_requests].
+    /// [Client::with_conditional_requests] remembers up to [DEFAULT_CONDITIONAL_CACHE_CAPACITY] responses.
+    ///
+    /// # Arguments
+    ///
+    /// * `capacity` - the maximum number of responses to remember, at least 1
+    pub fn with_conditional_cache_capacity(mut self, capacity: usize) -> Self {
+        self.conditional = Some(Arc::new(ConditionalCache::new(capacity)));
+        self
+    }
+
+    /// Forget the responses remembered for conditional requests, so the next request for each is sent unconditionally.
+    pub fn clear_conditional_cache(&self) {
+        if let Some(conditional) = &self.conditional {
+            conditional.clear();
+        }
+    }
+
+    /// Set whether a [Client] decodes responses leniently, which it doesn't by default.
+    ///
+    /// When enabled, items of a response's data that don't match the data model are dropped instead of failing the whole response,
+    /// and each one is described in [ResponseMeta::item_errors]. Only responses with multiple resources are decoded leniently.
+    ///
+    /// # Arguments
+    ///
+    /// * `enabled` - whether to decode responses leniently
+    pub fn with_lenient_decoding(mut self, enabled: bool) -> Self {
+        self.lenient = enabled;
+        self
+    }
+
+    /// Set whether a [Client] records fields the API sends that the data models don't declare, which it doesn't by default.
+    ///
+    /// When enabled, each resource of a successful response, whether in its data or its included resources,
+    /// is deserialized a second time on its own to find unknown attributes and relationships,
+    /// which [Client::schema_drift] then reports by resource type. This is meant for diagnostics, such as in a test suite
+    /// run against the live API, to tell when the data models lag behind it.
+    /// The record is shared between clones of the returned client.
+    ///
+    /// # Arguments
+    ///
+    /// * `enabled` - whether to detect schema drift
+    pub fn with_schema_drift_detection(mut self, enabled: bool) -> Self {
+        self.schema_drift = enabled.then(|| Arc::new(Mutex::new(SchemaDrift::default())));
+        self
+    }
+
+    /// Fields the API sent to this client or any of its clones that the data models don't declare,
+    /// which is always empty unless schema drift detection is enabled.
+    pub fn schema_drift(&self) -> SchemaDrift {
+        match &self.schema_drift {
+            Some(drift) => drift.lock().unwrap_or_else(|e| e.into_inner()).clone(),
+            None => SchemaDrift::default(),
+        }
+    }
+
+    /// Cache responses from the endpoints a [CachePolicy] covers, which a [Client] doesn't do by default.
+    ///
+    /// Responses are cached by endpoint and query parameters (in any order), and while fresh are returned
+    /// with [ResponseMeta::cached] set and without sending a request. Use a [MemoryCache] to cache for the life of the process,
+    /// or a [FileCache] to keep responses between runs. The cache is shared between clones of the returned client.
+    ///
+    /// # Arguments
+    ///
+    /// * `cache` - the cache to store responses in
+    /// * `policy` - how long responses from each endpoint stay fresh
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use mbta_rs::*;
+    ///
+    /// let client = Client::without_key().with_cache(MemoryCache::new(100), CachePolicy::static_data(Duration::from_secs(24 * 60 * 60)));
+    /// ```
+    pub fn with_cache<C: ResponseCache + 'static>(mut self, cache: C, policy: CachePolicy) -> Self {
+        self.cache = Some(Arc::new(cache));
+        self.cache_policy = policy;
+        self
+    }
+
+    /// Remove every response from the response cache, so the next request for each is sent to the API.
+    pub fn clear_cache(&self) {
+        if let Some(cache) = &self.cache {
+            cache.clear();
+        }
+    }
+
+    /// The most recent rate-limit state reported by the API to this client or any of its clones,
+    /// [None] if no response has reported one yet.
+    pub fn rate_limit(&self) -> Option<RateLimit> {
+        *self.rate_limit.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Replace the HTTP transport of a [Client], such as with an in-memory mock for testing.
+    /// The transport is shared between clones of the returned client.
+    ///
+    /// # Arguments
+    ///
+    /// * `transport` - the transport to send requests through
+    ///
+    /// ```
+    /// use mbta_rs::*;
+    ///
+    /// #[derive(Debug)]
+    /// struct EmptyTransport;
+    ///
+    /// impl Transport for EmptyTransport {
+    ///     fn send(&self, _request: &HttpRequest) -> Result<HttpResponse, ClientError> {
+    ///         Ok(HttpResponse {
+    ///             status: 200,
+    ///             headers: vec![],
+    ///             body: "{\"data\": [], \"jsonapi\": {\"version\": \"1.0\"}}".into(),
+    ///         })
+    ///     }
+    /// }
+    ///
+    /// let client = Client::without_key().with_transport(EmptyTransport);
+    /// let lines = client.lines(&[("page[limit]", "3")]).expect("failed to get lines");
+    /// assert!(lines.data.is_empty());
+    /// ```
+    pub fn with_transport<T: Transport + 'static>(mut self, transport: T) -> Self {
+        self.transport = Arc::new(transport);
+        self
+    }
+
+    /// Returns the status of the API, such as the version of the GTFS feed it is serving.
+    ///
+    /// ```
+    /// # use std::env;
+    /// # use mbta_rs::Client;
+    /// #
+    /// # let client = match env::var("MBTA_TOKEN") {
+    /// #     Ok(token) => Client::with_key(token),
+    /// #     Err(_) => Client::without_key()
+    /// # };
+    /// #
+    /// if let Ok(status) = client.status() {
+    ///     println!("{}", status.data.attributes.feed.version);
+    /// }
+    /// ```
+    pub fn status(&self) -> Result<Response<Status>, ClientError> {
+        self.fetch_one::<Status, String, String>("", &[])
+    }
+
+    /// Returns the JSON body of a `GET` request to any path with any query parameters, such as ones this crate doesn't know about yet.
+    ///
+    /// Unlike the endpoint methods, the query parameters aren't checked, and the response is never cached;
+    /// the retry and rate-limit policies still apply.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - the endpoint path, relative to the base URL, such as `"stops/place-sstat"`
+    /// * `query_params` - a slice of pairings of query parameter names to values
+    ///
+    /// ```
+    /// # use std::env;
+    /// # use mbta_rs::Client;
+    /// #
+    /// # let client = match env::var("MBTA_TOKEN") {
+    /// #     Ok(token) => Client::with_key(token),
+    /// #     Err(_) => Client::without_key()
+    /// # };
+    /// #
+    /// if let Ok(body) = client.get_raw("routes", &[("filter[type]", "0")]) {
+    ///     println!("{}", body["data"][0]["id"]);
+    /// }
+    /// ```
+    pub fn get_raw<K: AsRef<str>, V: AsRef<str>>(&self, path: &str, query_params: &[(K, V)]) -> Result<Value, ClientError> {
+        self.get_as(path, query_params)
+    }
+
+    /// Returns the body of a `GET` request to any path with any query parameters, deserialized as any type.
+    ///
+    /// Like [Client::get_raw], the query parameters aren't checked and the response is never cached.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - the endpoint path, relative to the base URL, such as `"stops/place-sstat"`
+    /// * `query_params` - a slice of pairings of query parameter names to values
+    ///
+    /// ```
+    /// # use std::env;
+    /// # use mbta_rs::*;
+    /// #
+    /// # let client = match env::var("MBTA_TOKEN") {
+    /// #     Ok(token) => Client::with_key(token),
+    /// #     Err(_) => Client::without_key()
+    /// # };
+    /// #
+    /// if let Ok(routes) = client.get_as::<Response<Routes>, _, _>("routes", &[("filter[type]", "0")]) {
+    ///     for route in routes.data {
+    ///         println!("{}", route.id);
+    ///     }
+    /// }
+    /// ```
+    pub fn get_as<T: DeserializeOwned, K: AsRef<str>, V: AsRef<str>>(&self, path: &str, query_params: &[(K, V)]) -> Result<T, ClientError> {
+        let request = self.config.request(path.trim_start_matches('/'), query_params);
+        let response = self.send(&request)?;
+        if !response.is_success() {
+            return Err(ClientError::from_response(&response));
+        }
+        deserialize(&request.url, &response.body)
+    }
+
+    /// Returns the resources of any endpoint with multiple return objects, such as `client.fetch::<Stops, _, _>(&query_params)`.
+    ///
+    /// Equivalent to the endpoint's own method, such as [Client::stops]; only accepts a [MultipleEndpoint].
+    ///
+    /// # Arguments
+    ///
+    /// * `query_params` - a slice of pairings of query parameter names to values, limited to [Endpoint::ALLOWED_QUERY_PARAMS]
+    ///
+    /// ```
+    /// # use std::env;
+    /// # use mbta_rs::*;
+    /// #
+    /// # let client = match env::var("MBTA_TOKEN") {
+    /// #     Ok(token) => Client::with_key(token),
+    /// #     Err(_) => Client::without_key()
+    /// # };
+    /// #
+    /// if let Ok(stops) = client.fetch::<Stops, _, _>(&[("filter[route]", "Red")]) {
+    ///     for stop in stops.data {
+    ///         println!("{}", stop.attributes.name);
+    ///     }
+    /// }
+    /// ```
+    pub fn fetch<E: MultipleEndpoint, K: AsRef<str>, V: AsRef<str>>(
+        &self,
+        query_params: &[(K, V)],
+    ) -> Result<Response<E::Model>, ClientError> {
+        validate_query_params(E::ALLOWED_QUERY_PARAMS, query_params)?;
+        self.get(E::PATH, query_params)
+    }
+
+    /// Returns a lazy iterator over the pages of any endpoint with multiple return objects, following the `next` link of each page.
+    ///
+    /// Equivalent to the endpoint's own pagination method, such as [Client::stops_pages]; only accepts a [MultipleEndpoint].
+    ///
+    /// # Arguments
+    ///
+    /// * `query_params` - a slice of pairings of query parameter names to values, limited to [Endpoint::ALLOWED_QUERY_PARAMS]
+    pub fn fetch_pages<E: MultipleEndpoint, K: AsRef<str>, V: AsRef<str>>(
+        &self,
+        query_params: &[(K, V)],
+    ) -> Result<Pages<'_, E::Model>, ClientError> {
+        validate_query_params(E::ALLOWED_QUERY_PARAMS, query_params)?;
+        Ok(Pages::new(self, E::PATH, query_params))
+    }
+
+    /// Returns the resource of any endpoint with single return objects given its id, such as `client.fetch_one::<Stop, _, _>(id, &[])`.
+    ///
+    /// Equivalent to the endpoint's own method, such as [Client::stop_with_params]; only accepts a [SingleEndpoint].
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - the id of the resource to return
+    /// * `query_params` - a slice of pairings of query parameter names to values, limited to [Endpoint::ALLOWED_QUERY_PARAMS]
+    pub fn fetch_one<E: SingleEndpoint, K: AsRef<str>, V: AsRef<str>>(
+        &self,
+        id: &str,
+        query_params: &[(K, V)],
+    ) -> Result<Response<E::Model>, ClientError> {
+        validate_query_params(E::ALLOWED_QUERY_PARAMS, query_params)?;
+        self.get(&E::path(id), query_params)
+    }
+
+    /// Returns a stream of events from any endpoint that streams server-sent events, such as `client.stream::<Vehicles, _, _>(&query_params)`.
+    ///
+    /// Equivalent to the endpoint's own streaming method, such as [Client::stream_vehicles]; only accepts a [StreamEndpoint].
+    ///
+    /// # Arguments
+    ///
+    /// * `query_params` - a slice of pairings of query parameter names to values, limited to [StreamEndpoint::STREAM_QUERY_PARAMS]
+    pub fn stream<E: StreamEndpoint, K: AsRef<str>, V: AsRef<str>>(
+        &self,
+        query_params: &[(K, V)],
+    ) -> Result<EventStream<'_, E::Item>, ClientError> {
+        validate_query_params(E::STREAM_QUERY_PARAMS, query_params)?;
+        Ok(EventStream::new(self, E::PATH, query_params))
+    }
+
     /// Helper method for making generalized `GET` requests to any endpoint with any query parameters.
     /// Presumes that all query parameters given are valid.
     ///
     /// # Arguments
     ///
     /// * query_params - a slice of pairings of query parameter names to values
-    fn get<T: DeserializeOwned, K: AsRef<str>, V: AsRef<str>>(
+    pub(crate) fn get<T, K: AsRef<str>, V: AsRef<str>>(&self, endpoint: &str, query_params: &[(K, V)]) -> Result<Response<T>, ClientError>
+    where
+        T: DeserializeOwned + Clone + Send + Sync + 'static,
+    {
+        let started = Instant::now();
+        let mut request = self.config.request(endpoint, query_params);
+        let key = ConditionalCache::key(&request);
+        let cache = self.cache.as_ref().filter(|_| self.cache_policy.ttl(endpoint).is_some());
+        if let Some((mut cached, item_errors)) = cache.and_then(|cache| self.cached::<T>(cache.as_ref(), endpoint, &request.url, &key)) {
+            let response = HttpResponse {
+                status: 200,
+                headers: vec![],
+                body: String::new(),
+            };
+            cached.meta = ResponseMeta {
+                cached: true,
+                item_errors,
+                ..ResponseMeta::new(&request, &response, started.elapsed())
+            };
+            return Ok(cached);
+        }
+        if let Some(last_modified) = self.conditional.as_ref().and_then(|conditional| conditional.last_modified(&key)) {
+            request.headers.push((IF_MODIFIED_SINCE_HEADER.into(), last_modified));
+        }
+        let mut response = self.send(&request)?;
+        if let (NOT_MODIFIED, Some(conditional)) = (response.status, &self.conditional) {
+            if let Some(mut cached) = conditional.get::<T>(&key) {
+                cached.meta = ResponseMeta {
+                    item_errors: cached.meta.item_errors,
+                    ..ResponseMeta::new(&request, &response, started.elapsed())
+                };
+                return Ok(cached);
+            }
+            request.headers.retain(|(name, _)| name != IF_MODIFIED_SINCE_HEADER);
+            response = self.send(&request)?;
+        }
+        if let Some(drift) = self.schema_drift.as_ref().filter(|_| response.is_success()) {
+            drift.lock().unwrap_or_else(|e| e.into_inner()).record(&response.body);
+        }
+        let parsed = parse_response(&request, &response, started, self.lenient)?;
+        if let (Some(conditional), Some(last_modified)) = (&self.conditional, response.header(LAST_MODIFIED_HEADER)) {
+            conditional.insert(key.clone(), last_modified.into(), &parsed);
+        }
+        if let Some(cache) = cache {
+            let stored = CachedResponse {
+                body: response.body,
+                stored_at: SystemTime::now(),
+            };
+            cache.put(&key, stored);
+        }
+        Ok(parsed)
+    }
+
+    /// Helper method for getting a fresh response from the response cache, if there is one that still parses.
+    /// The metadata of the response is left for the caller to fill in.
+    ///
+    /// # Arguments
+    ///
+    /// * `cache` - the response cache
+    /// * `endpoint` - the endpoint path
+    /// * `url` - the URL of the request
+    /// * `key` - the request key
+    fn cached<T: DeserializeOwned>(
         &self,
+        cache: &dyn ResponseCache,
         endpoint: &str,
-        query_params: &[(K, V)],
-    ) -> Result<Response<T>, ClientError> {
-        let path = format!("{}/{}", self.base_url, endpoint);
-        let request = ureq::get(&path);
-        let request = match &self.api_key {
-            Some(key) => request.set("x-api-key", key),
-            None => request,
-        };
-        let request = query_params.iter().fold(request, |r, (k, v)| r.query(k.as_ref(), v.as_ref()));
-        let response: Response<T> = request.call()?.into_json()?;
+        url: &str,
+        key: &str,
+    ) -> Option<(Response<T>, Vec<ItemDecodeError>)> {
+        let cached = cache.get(key).filter(|cached| self.cache_policy.is_fresh(endpoint, cached, SystemTime::now()))?;
+        decode(url, &cached.body, self.lenient).ok()
+    }
+
+    /// Helper method for sending a request through the transport while following the retry and rate-limit policies.
+    /// If the request was retried and the last attempt still failed, the error records every attempt.
+    ///
+    /// # Arguments
+    ///
+    /// * `request` - the request to send
+    pub(crate) fn send(&self, request: &HttpRequest) -> Result<HttpResponse, ClientError> {
+        let mut attempts = Vec::new();
+        loop {
+            let result = self.send_once(request);
+            let attempt = attempts.len() as u32 + 1;
+            // the rate-limit policy refused to wait that long, so retrying sooner won't help
+            let rate_limited = matches!(result, Err(ClientError::RateLimited { .. }));
+            if rate_limited || !is_transient(&result) || !self.retry_policy.should_retry(attempt, request.method) {
+                return with_attempts(result, attempts);
+            }
+            let backoff = self.retry_policy.backoff(attempt);
+            attempts.push(RetryAttempt {
+                outcome: attempt_outcome(&result),
+                backoff: Some(backoff),
+            });
+            thread::sleep(backoff);
+        }
+    }
+
+    /// Helper method for sending a request through the transport once while following the rate-limit policy.
+    ///
+    /// # Arguments
+    ///
+    /// * `request` - the request to send
+    fn send_once(&self, request: &HttpRequest) -> Result<HttpResponse, ClientError> {
+        self.wait_for_rate_limit()?;
+        let mut response = self.transport.send(request)?;
+        self.record_rate_limit(&response.headers);
+        if response.status == TOO_MANY_REQUESTS && self.rate_limit_policy.retries_when_limited() {
+            // without a reset time, resending right away would only land in the same exhausted window,
+            // so the response is left to become a RateLimited error or be retried by the retry policy
+            if let Some(reset_at) = reset_at(&response.headers) {
+                thread::sleep(self.rate_limit_policy.until_reset(reset_at, Utc::now())?);
+                response = self.transport.send(request)?;
+                self.record_rate_limit(&response.headers);
+            }
+        }
         Ok(response)
     }
+
+    /// Sleep for as long as the rate-limit policy asks before sending a request,
+    /// or fail if that's longer than the policy's maximum wait.
+    fn wait_for_rate_limit(&self) -> Result<(), ClientError> {
+        let delay = self.rate_limit_policy.delay(self.rate_limit().as_ref(), Utc::now())?;
+        if !delay.is_zero() {
+            thread::sleep(delay);
+        }
+        Ok(())
+    }
+
+    /// Remember the rate-limit state reported by a response, if any.
+    ///
+    /// # Arguments
+    ///
+    /// * `headers` - the response headers
+    fn record_rate_limit(&self, headers: &[(String, String)]) {
+        if let Some(rate_limit) = RateLimit::from_headers(headers) {
+            *self.rate_limit.lock().unwrap_or_else(|e| e.into_inner()) = Some(rate_limit);
+        }
+    }
+
+    /// Helper method for opening a stream of server-sent events from any endpoint with any query parameters.
+    /// Presumes that all query parameters given are valid.
+    ///
+    /// # Arguments
+    ///
+    /// * `endpoint` - the endpoint path, relative to the base URL
+    /// * `query_params` - a slice of pairings of query parameter names to values
+    /// * `last_event_id` - the ID of the last event received, if resuming a stream
+    pub(crate) fn open_stream<K: AsRef<str>, V: AsRef<str>>(
+        &self,
+        endpoint: &str,
+        query_params: &[(K, V)],
+        last_event_id: Option<&str>,
+    ) -> Result<HttpStream, ClientError> {
+        let mut request = self.config.request(endpoint, query_params);
+        request.headers.push(("accept".into(), "text/event-stream".into()));
+        if let Some(id) = last_event_id {
+            request.headers.push(("last-event-id".into(), id.into()));
+        }
+        self.wait_for_rate_limit()?;
+        let stream = self.transport.stream(&request)?;
+        self.record_rate_limit(&stream.headers);
+        if !stream.is_success() {
+            return Err(ClientError::from_response(&stream.into_response()?));
+        }
+        Ok(stream)
+    }
+}
+
+/// Helper function for attaching the earlier attempts of a retried request to the result of its final attempt,
+/// if the final attempt failed.
+///
+/// # Arguments
+///
+/// * `result` - the result of the final attempt
+/// * `attempts` - the earlier attempts, empty if the request wasn't retried
+fn with_attempts(result: Result<HttpResponse, ClientError>, mut attempts: Vec<RetryAttempt>) -> Result<HttpResponse, ClientError> {
+    if attempts.is_empty() || matches!(&result, Ok(response) if response.status < 400) {
+        return result;
+    }
+    attempts.push(RetryAttempt {
+        outcome: attempt_outcome(&result),
+        backoff: None,
+    });
+    let error = match result {
+        Ok(response) => ClientError::from_response(&response),
+        Err(e) => e,
+    };
+    Err(ClientError::RetriesExhausted {
+        attempts,
+        source: Box::new(error),
+    })
+}
+
+/// Helper function for turning an HTTP response into either an API response or an error.
+///
+/// # Arguments
+///
+/// * `request` - the HTTP request the response is to
+/// * `response` - the HTTP response
+/// * `started` - when sending the request started
+/// * `lenient` - whether to drop invalid items instead of failing
+pub(crate) fn parse_response<T: DeserializeOwned>(
+    request: &HttpRequest,
+    response: &HttpResponse,
+    started: Instant,
+    lenient: bool,
+) -> Result<Response<T>, ClientError> {
+    if !response.is_success() {
+        return Err(ClientError::from_response(response));
+    }
+    let (mut parsed, item_errors) = decode(&request.url, &response.body, lenient)?;
+    parsed.meta = ResponseMeta {
+        item_errors,
+        ..ResponseMeta::new(request, response, started.elapsed())
+    };
+    Ok(parsed)
 }
 
 #[cfg(test)]
@@ -344,10 +1284,7 @@ mod tests {
     #[rstest]
     fn test_client_without_key() {
         // Arrange
-        let expected = Client {
-            api_key: None,
-            base_url: "https://api-v3.mbta.com".into(),
-        };
+        let expected = Client::from_parts(ClientConfig::new("https://api-v3.mbta.com", None), UreqTransport::new());
 
         // Act
         let actual = Client::without_key();
@@ -359,10 +1296,8 @@ mod tests {
     #[rstest]
     fn test_client_with_key() {
         // Arrange
-        let expected = Client {
-            api_key: Some("test key".into()),
-            base_url: "https://api-v3.mbta.com".into(),
-        };
+        let expected =
+            Client::from_parts(ClientConfig::new("https://api-v3.mbta.com", Some(ApiKey::header("test key"))), UreqTransport::new());
 
         // Act
         let actual = Client::with_key("test key");
@@ -374,10 +1309,7 @@ mod tests {
     #[rstest]
     fn test_client_with_url() {
         // Arrange
-        let expected = Client {
-            api_key: None,
-            base_url: "https://foobar.com".into(),
-        };
+        let expected = Client::from_parts(ClientConfig::new("https://foobar.com", None), UreqTransport::new());
 
         // Act
         let actual = Client::with_url("https://foobar.com");
@@ -385,4 +1317,30 @@ mod tests {
         // Assert
         assert_eq!(actual, expected);
     }
+
+    #[rstest]
+    fn test_client_debug_redacts_key() {
+        // Arrange
+        let client = Client::with_key("secret key");
+
+        // Act
+        let actual = format!("{:?}", client);
+
+        // Assert
+        assert!(!actual.contains("secret key"));
+        assert!(actual.contains("[redacted]"));
+    }
+
+    #[rstest]
+    fn test_client_clones_share_transport() {
+        // Arrange
+        let client = Client::with_key("test key");
+
+        // Act
+        let clone = client.clone();
+
+        // Assert
+        assert!(Arc::ptr_eq(&client.transport, &clone.transport));
+        assert!(!Arc::ptr_eq(&client.transport, &Client::with_key("test key").transport));
+    }
 }
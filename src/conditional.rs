@@ -0,0 +1,188 @@
+//! Conditional requests, which let the API answer `304 Not Modified` instead of resending unchanged data.
+
+use std::{
+    any::Any,
+    fmt::{Debug, Formatter, Result as FmtResult},
+    sync::{Arc, Mutex, MutexGuard},
+};
+
+use super::*;
+
+/// Name of the response header with when the data last changed.
+pub const LAST_MODIFIED_HEADER: &str = "last-modified";
+
+/// Name of the request header asking for data only if it changed after a given time.
+pub const IF_MODIFIED_SINCE_HEADER: &str = "if-modified-since";
+
+/// HTTP status code of responses to conditional requests whose data hasn't changed.
+pub const NOT_MODIFIED: u16 = 304;
+
+/// Default maximum number of responses a [Client] remembers for conditional requests.
+pub const DEFAULT_CONDITIONAL_CACHE_CAPACITY: usize = 256;
+
+/// Most recent response to a request, along with when its data last changed.
+struct ConditionalEntry {
+    /// Value of the `Last-Modified` header of the response.
+    last_modified: String,
+    /// The parsed response, as a `Response<T>`.
+    response: Arc<dyn Any + Send + Sync>,
+}
+
+/// Cache of the most recent response to each distinct request, keyed by URL plus sorted query parameters,
+/// for sending conditional requests and answering them when the API says nothing changed.
+/// Evicts the least recently used response once full.
+pub(crate) struct ConditionalCache {
+    /// Maximum number of responses.
+    capacity: usize,
+    /// Cached responses by request key.
+    entries: Mutex<LruEntries<ConditionalEntry>>,
+}
+
+impl ConditionalCache {
+    /// Create an empty [ConditionalCache].
+    ///
+    /// # Arguments
+    ///
+    /// * `capacity` - the maximum number of responses to keep, at least 1
+    pub(crate) fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            entries: Mutex::new(LruEntries::default()),
+        }
+    }
+
+    /// Key identifying a request regardless of the order of its query parameters.
+    /// An API key sent as a query parameter is left out, so keys can be stored without leaking it.
+    ///
+    /// # Arguments
+    ///
+    /// * `request` - the request
+    pub(crate) fn key(request: &HttpRequest) -> String {
+        let mut query = request.query.clone();
+        query.retain(|(name, _)| name != API_KEY_QUERY_PARAM);
+        query.sort();
+        let query: Vec<String> = query.into_iter().map(|(k, v)| format!("{}={}", k, v)).collect();
+        format!("{}?{}", request.url, query.join("&"))
+    }
+
+    /// When the data of the cached response to a request last changed, if there is one.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - the request key
+    pub(crate) fn last_modified(&self, key: &str) -> Option<String> {
+        self.lock().get(key).map(|entry| entry.last_modified.clone())
+    }
+
+    /// The cached response to a request, if there is one with the given data type.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - the request key
+    pub(crate) fn get<T: Clone + 'static>(&self, key: &str) -> Option<Response<T>> {
+        self.lock().get(key)?.response.downcast_ref::<Response<T>>().cloned()
+    }
+
+    /// Cache a response to a request.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - the request key
+    /// * `last_modified` - the value of the `Last-Modified` header of the response
+    /// * `response` - the parsed response
+    pub(crate) fn insert<T: Clone + Send + Sync + 'static>(&self, key: String, last_modified: String, response: &Response<T>) {
+        let entry = ConditionalEntry {
+            last_modified,
+            response: Arc::new(response.clone()),
+        };
+        self.lock().put(&key, entry, self.capacity);
+    }
+
+    /// Forget all cached responses.
+    pub(crate) fn clear(&self) {
+        self.lock().clear();
+    }
+
+    /// Lock the entries, recovering from a poisoned lock since the entries are always left consistent.
+    fn lock(&self) -> MutexGuard<'_, LruEntries<ConditionalEntry>> {
+        self.entries.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl Debug for ConditionalCache {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        f.debug_struct("ConditionalCache")
+            .field("capacity", &self.capacity)
+            .field("entries", &self.lock().len())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use rstest::*;
+
+    fn request(query: &[(&str, &str)]) -> HttpRequest {
+        ClientConfig::new("https://foobar.com", None).request("vehicles", query)
+    }
+
+    fn response() -> Response<Vec<Shape>> {
+        serde_json::from_str("{\"data\": [], \"jsonapi\": {\"version\": \"1.0\"}}").expect("invalid response")
+    }
+
+    #[rstest]
+    fn test_key_ignores_query_order() {
+        // Arrange
+        let first = request(&[("filter[route]", "Red"), ("filter[direction_id]", "0")]);
+        let second = request(&[("filter[direction_id]", "0"), ("filter[route]", "Red")]);
+
+        // Act
+        let keys = (ConditionalCache::key(&first), ConditionalCache::key(&second));
+
+        // Assert
+        assert_eq!(keys.0, keys.1);
+        assert_eq!(keys.0, "https://foobar.com/vehicles?filter[direction_id]=0&filter[route]=Red");
+    }
+
+    #[rstest]
+    fn test_cache_round_trip() {
+        // Arrange
+        let cache = ConditionalCache::new(DEFAULT_CONDITIONAL_CACHE_CAPACITY);
+        let key = ConditionalCache::key(&request(&[]));
+
+        // Act
+        cache.insert(key.clone(), "Sat, 02 Apr 2022 19:14:06 GMT".into(), &response());
+
+        // Assert
+        assert_eq!(cache.last_modified(&key), Some("Sat, 02 Apr 2022 19:14:06 GMT".into()));
+        assert_eq!(cache.get::<Vec<Shape>>(&key), Some(response()));
+        assert_eq!(cache.get::<Vec<Stop>>(&key), None);
+        assert_eq!(cache.get::<Vec<Shape>>("foobar"), None);
+        cache.clear();
+        assert_eq!(cache.last_modified(&key), None);
+    }
+
+    #[rstest]
+    fn test_cache_evicts_least_recently_used() {
+        // Arrange
+        let cache = ConditionalCache::new(2);
+        let keys: Vec<String> = ["Red", "Orange", "Blue"]
+            .iter()
+            .map(|route| ConditionalCache::key(&request(&[("filter[route]", route)])))
+            .collect();
+        cache.insert(keys[0].clone(), "Sat, 02 Apr 2022 19:14:06 GMT".into(), &response());
+        cache.insert(keys[1].clone(), "Sat, 02 Apr 2022 19:14:06 GMT".into(), &response());
+
+        // Act
+        cache.get::<Vec<Shape>>(&keys[0]);
+        cache.insert(keys[2].clone(), "Sat, 02 Apr 2022 19:14:06 GMT".into(), &response());
+
+        // Assert
+        assert_eq!(cache.lock().len(), 2);
+        assert_eq!(cache.get::<Vec<Shape>>(&keys[0]), Some(response()));
+        assert_eq!(cache.get::<Vec<Shape>>(&keys[1]), None);
+        assert_eq!(cache.get::<Vec<Shape>>(&keys[2]), Some(response()));
+    }
+}
@@ -0,0 +1,223 @@
+//! Schema-drift detection, which finds fields the API sends that the data models don't declare.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::{Display, Formatter, Result as FmtResult},
+};
+
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+use super::*;
+
+/// Fields the API sent that the data models don't declare, by resource type.
+///
+/// Fields are given as paths within a resource, such as `attributes.carriages` or `relationships.occupancies`,
+/// with array indices left out. Fields outside of any resource are reported under the `response` type,
+/// and resources of a type no data model is for are reported with the field `type`.
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct SchemaDrift {
+    /// Unknown field paths by resource type.
+    models: BTreeMap<String, BTreeSet<String>>,
+}
+
+impl SchemaDrift {
+    /// Returns whether no unknown fields have been seen.
+    pub fn is_empty(&self) -> bool {
+        self.models.is_empty()
+    }
+
+    /// Resource types with unknown fields, in alphabetical order.
+    pub fn models(&self) -> impl Iterator<Item = &str> {
+        self.models.keys().map(String::as_str)
+    }
+
+    /// Unknown fields of a resource type, in alphabetical order.
+    ///
+    /// # Arguments
+    ///
+    /// * `model` - the resource type, such as `"vehicle"`
+    pub fn fields(&self, model: &str) -> impl Iterator<Item = &str> {
+        self.models.get(model).into_iter().flatten().map(String::as_str)
+    }
+
+    /// One warning per unknown field, such as ``vehicle: unknown field `attributes.carriages` ``.
+    pub fn warnings(&self) -> Vec<String> {
+        self.models
+            .iter()
+            .flat_map(|(model, fields)| fields.iter().map(move |field| format!("{}: unknown field `{}`", model, field)))
+            .collect()
+    }
+
+    /// Record the unknown fields of a response body.
+    /// Each resource in `data` and `included` is checked on its own against the data model for its type,
+    /// and resources that don't match their data model are skipped, since their errors are reported elsewhere.
+    ///
+    /// # Arguments
+    ///
+    /// * `body` - the response body
+    pub(crate) fn record(&mut self, body: &str) {
+        let value: Value = match serde_json::from_str(body) {
+            Ok(value) => value,
+            Err(_) => return,
+        };
+        let mut ignored = vec![];
+        if serde_ignored::deserialize::<_, _, Response<Value>>(&value, |path| ignored.push(path.to_string())).is_ok() {
+            for path in ignored {
+                self.insert("response".into(), field(&path));
+            }
+        }
+        for resource in resources(&value) {
+            let model = resource["type"].as_str().unwrap_or_default();
+            let checked = match model {
+                Alert::RESOURCE_TYPE => check::<Alert>(resource),
+                Facility::RESOURCE_TYPE => check::<Facility>(resource),
+                Line::RESOURCE_TYPE => check::<Line>(resource),
+                LiveFacility::RESOURCE_TYPE => check::<LiveFacility>(resource),
+                Prediction::RESOURCE_TYPE => check::<Prediction>(resource),
+                Route::RESOURCE_TYPE => check::<Route>(resource),
+                RoutePattern::RESOURCE_TYPE => check::<RoutePattern>(resource),
+                Schedule::RESOURCE_TYPE => check::<Schedule>(resource),
+                Service::RESOURCE_TYPE => check::<Service>(resource),
+                Shape::RESOURCE_TYPE => check::<Shape>(resource),
+                Status::RESOURCE_TYPE => check::<Status>(resource),
+                Stop::RESOURCE_TYPE => check::<Stop>(resource),
+                Trip::RESOURCE_TYPE => check::<Trip>(resource),
+                Vehicle::RESOURCE_TYPE => check::<Vehicle>(resource),
+                _ => Some(vec!["type".into()]),
+            };
+            for field in checked.into_iter().flatten() {
+                self.insert(model.into(), field);
+            }
+        }
+    }
+
+    /// Record an unknown field of a resource type.
+    fn insert(&mut self, model: String, field: String) {
+        self.models.entry(model).or_default().insert(field);
+    }
+}
+
+impl Display for SchemaDrift {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "{}", self.warnings().join("\n"))
+    }
+}
+
+/// Unknown fields of a resource, found by deserializing it on its own as a data model,
+/// or [None] if it doesn't match the data model.
+/// Relationships are kept in a map, so any without an accessor are reported by name.
+///
+/// # Arguments
+///
+/// * `resource` - the resource as JSON
+fn check<M: Model + DeserializeOwned>(resource: &Value) -> Option<Vec<String>> {
+    let mut fields = vec![];
+    serde_ignored::deserialize::<_, _, M>(resource, |path| fields.push(field(&path.to_string()))).ok()?;
+    let relationships = resource["relationships"].as_object().into_iter().flat_map(|r| r.keys());
+    fields.extend(
+        relationships
+            .filter(|name| !M::RELATIONSHIPS.contains(&name.as_str()))
+            .map(|name| format!("relationships.{}", name)),
+    );
+    Some(fields)
+}
+
+/// Turn the path of an ignored field into a field path, leaving out array indices and optional markers.
+///
+/// # Arguments
+///
+/// * `path` - the path of the ignored field, such as `attributes.carriages.0.?.label`
+fn field(path: &str) -> String {
+    let named: Vec<&str> = path.split('.').filter(|segment| *segment != "?" && segment.parse::<usize>().is_err()).collect();
+    named.join(".")
+}
+
+/// Every resource in a response body, from both `data` and `included`.
+///
+/// # Arguments
+///
+/// * `value` - the whole response body
+fn resources(value: &Value) -> Vec<&Value> {
+    let mut resources = vec![];
+    match &value["data"] {
+        Value::Array(items) => resources.extend(items),
+        Value::Object(_) => resources.push(&value["data"]),
+        _ => {}
+    }
+    resources.extend(value["included"].as_array().into_iter().flatten());
+    resources
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use rstest::*;
+
+    #[rstest]
+    fn test_record_unknown_fields() {
+        // Arrange
+        let body = "{\"data\": [{\"type\": \"shape\", \"id\": \"a\", \"attributes\": {\"polyline\": \"abc\", \"color\": \"red\"}, \"relationships\": {\"route\": {\"data\": {\"type\": \"route\", \"id\": \"Red\"}}}}], \"jsonapi\": {\"version\": \"1.0\"}, \"meta\": {}}";
+        let mut drift = SchemaDrift::default();
+
+        // Act
+        drift.record(body);
+
+        // Assert
+        assert_eq!(drift.models().collect::<Vec<_>>(), vec!["response", "shape"]);
+        assert_eq!(drift.fields("shape").collect::<Vec<_>>(), vec!["attributes.color", "relationships.route"]);
+        assert_eq!(drift.fields("response").collect::<Vec<_>>(), vec!["meta"]);
+        assert_eq!(
+            drift.to_string(),
+            "response: unknown field `meta`\nshape: unknown field `attributes.color`\nshape: unknown field `relationships.route`"
+        );
+    }
+
+    #[rstest]
+    fn test_record_unknown_fields_in_included() {
+        // Arrange
+        let body = "{\"data\": [], \"jsonapi\": {\"version\": \"1.0\"}, \"included\": [{\"type\": \"shape\", \"id\": \"a\", \"attributes\": {\"polyline\": \"abc\", \"color\": \"red\"}}, {\"type\": \"occupancy\", \"id\": \"b\", \"attributes\": {}}]}";
+        let mut drift = SchemaDrift::default();
+
+        // Act
+        drift.record(body);
+
+        // Assert
+        assert_eq!(drift.models().collect::<Vec<_>>(), vec!["occupancy", "shape"]);
+        assert_eq!(drift.fields("shape").collect::<Vec<_>>(), vec!["attributes.color"]);
+        assert_eq!(drift.fields("occupancy").collect::<Vec<_>>(), vec!["type"]);
+    }
+
+    #[rstest]
+    fn test_record_skips_invalid_items() {
+        // Arrange
+        let body = "{\"data\": [{\"type\": \"shape\", \"id\": \"a\", \"attributes\": {\"polyline\": 1, \"width\": 3}}, {\"type\": \"shape\", \"id\": \"b\", \"attributes\": {\"polyline\": \"abc\", \"color\": \"red\"}}], \"jsonapi\": {\"version\": \"1.0\"}}";
+        let mut drift = SchemaDrift::default();
+
+        // Act
+        drift.record(body);
+
+        // Assert
+        assert_eq!(drift.models().collect::<Vec<_>>(), vec!["shape"]);
+        assert_eq!(drift.fields("shape").collect::<Vec<_>>(), vec!["attributes.color"]);
+    }
+
+    #[rstest]
+    #[case::known(
+        "{\"data\": [{\"type\": \"shape\", \"id\": \"a\", \"attributes\": {\"polyline\": \"abc\"}}], \"jsonapi\": {\"version\": \"1.0\"}}"
+    )]
+    #[case::invalid(
+        "{\"data\": [{\"type\": \"shape\", \"id\": \"a\", \"attributes\": {\"color\": \"red\"}}], \"jsonapi\": {\"version\": \"1.0\"}}"
+    )]
+    fn test_record_nothing(#[case] body: &str) {
+        // Arrange
+        let mut drift = SchemaDrift::default();
+
+        // Act
+        drift.record(body);
+
+        // Assert
+        assert!(drift.is_empty());
+    }
+}
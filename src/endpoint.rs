@@ -0,0 +1,86 @@
+//! Endpoints of the V3 API as types, for requests that are generic over the data model.
+
+use serde::de::DeserializeOwned;
+
+use crate::Identified;
+
+/// An endpoint of the V3 API, described by its path, data model, and allowed query parameters.
+///
+/// Every endpoint of [Client](crate::Client) is implemented on its data model, such as [Stops](crate::Stops) and [Stop](crate::Stop),
+/// along with [MultipleEndpoint] or [SingleEndpoint] to say how it is requested,
+/// so requests can be made generically with [Client::fetch](crate::Client::fetch) and [Client::fetch_one](crate::Client::fetch_one).
+/// Endpoints this crate doesn't know about can be added by implementing these traits on a marker type.
+///
+/// ```no_run
+/// use mbta_rs::*;
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Deserialize, Serialize, Debug, PartialEq, Clone)]
+/// struct OccupancyAttributes {
+///     status: String,
+/// }
+///
+/// #[derive(Debug)]
+/// struct Occupancies;
+///
+/// impl Endpoint for Occupancies {
+///     type Model = Vec<Resource<OccupancyAttributes>>;
+///     const PATH: &'static str = "occupancies";
+///     const ALLOWED_QUERY_PARAMS: &'static [&'static str] = &["page[offset]", "page[limit]", "filter[stop]"];
+/// }
+///
+/// impl MultipleEndpoint for Occupancies {}
+///
+/// # let client = Client::without_key();
+/// if let Ok(occupancies) = client.fetch::<Occupancies, _, _>(&[("filter[stop]", "place-sstat")]) {
+///     for item in occupancies.data {
+///         println!("{}", item.attributes.status);
+///     }
+/// }
+/// ```
+///
+/// Requesting an endpoint the wrong way is a type error:
+///
+/// ```compile_fail
+/// # use mbta_rs::*;
+/// # let client = Client::without_key();
+/// let stops = client.fetch_one::<Stops, _, _>("place-sstat", &[("include", "route")]);
+/// ```
+pub trait Endpoint {
+    /// Data model the endpoint returns, a [Vec] of resources for endpoints with multiple return objects.
+    type Model: DeserializeOwned + Clone + Send + Sync + 'static;
+
+    /// Path of the endpoint relative to the base URL, without the id for endpoints with single return objects.
+    const PATH: &'static str;
+
+    /// Query parameters the endpoint allows; requests with any others fail before being sent.
+    const ALLOWED_QUERY_PARAMS: &'static [&'static str];
+}
+
+/// An [Endpoint] that returns multiple resources, such as `/stops`;
+/// requested with [Client::fetch](crate::Client::fetch) and [Client::fetch_pages](crate::Client::fetch_pages).
+pub trait MultipleEndpoint: Endpoint {}
+
+/// An [Endpoint] that returns a single resource given its id, such as `/stops/{id}`;
+/// requested with [Client::fetch_one](crate::Client::fetch_one).
+pub trait SingleEndpoint: Endpoint {
+    /// Path of the resource with the given id relative to the base URL, which is the endpoint's path followed by the id.
+    /// Endpoints with only one resource, such as `/status`, ignore the id.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - the id of the resource
+    fn path(id: &str) -> String {
+        format!("{}/{}", Self::PATH, id)
+    }
+}
+
+/// A [MultipleEndpoint] that can also stream server-sent events, such as `/vehicles`;
+/// streamed with [Client::stream](crate::Client::stream).
+pub trait StreamEndpoint: MultipleEndpoint {
+    /// Data model of each resource in the stream.
+    type Item: DeserializeOwned + Identified<Id: DeserializeOwned>;
+
+    /// Query parameters the endpoint allows when streaming; streams with any others fail before connecting.
+    const STREAM_QUERY_PARAMS: &'static [&'static str];
+}
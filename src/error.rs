@@ -7,11 +7,16 @@ use std::{
     io::Error as IOError,
 };
 
-use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::Value;
 use thiserror::Error;
 use ureq::{Error as RequestError, Transport};
 
-use super::APIVersion;
+use super::{reset_at, APIVersion, HttpResponse, IncludedResource, RetryAttempt, TOO_MANY_REQUESTS};
+
+/// Maximum number of characters of a response body kept in an error.
+pub const ERROR_SNIPPET_LENGTH: usize = 200;
 
 /// All possible errors that can occur when using the client.
 #[derive(Debug, Error)]
@@ -19,15 +24,60 @@ pub enum ClientError {
     /// I/O Error.
     #[error("some kind of I/O error occured: `{0}`")]
     IOError(#[from] IOError),
-    /// HTTP response error.
-    #[error("HTTP response error: `{errors:?}`")]
+    /// HTTP response error with a status code not covered by a more specific variant.
+    #[error("HTTP {status} response error: `{errors:?}`")]
     ResponseError {
+        /// HTTP status code.
+        status: u16,
+        /// Response errors.
+        errors: APIErrorResponse,
+    },
+    /// Bad request (HTTP 400), such as an unknown sort or filter value.
+    #[error("bad request: {errors}")]
+    BadRequest {
+        /// Response errors.
+        errors: APIErrorResponse,
+    },
+    /// Forbidden (HTTP 403), usually because the API key is invalid.
+    #[error("forbidden, the API key may be invalid: {errors}")]
+    Forbidden {
+        /// Response errors.
+        errors: APIErrorResponse,
+    },
+    /// Not found (HTTP 404), such as a resource with an unknown ID.
+    #[error("not found: {errors}")]
+    NotFound {
         /// Response errors.
         errors: APIErrorResponse,
     },
+    /// Server error (HTTP 5xx).
+    #[error("server error (HTTP {status}): `{body}`")]
+    ServerError {
+        /// HTTP status code.
+        status: u16,
+        /// The start of the response body.
+        body: String,
+    },
+    /// Unsuccessful HTTP response whose body isn't an API error response, such as an error page from a proxy.
+    #[error("unexpected HTTP {status} response: `{body}`")]
+    UnexpectedResponse {
+        /// HTTP status code.
+        status: u16,
+        /// The start of the response body.
+        body: String,
+    },
+    /// Successful HTTP response whose body doesn't match the expected data model.
+    #[error(
+        "failed to deserialize response from `{}` at `{}`: {} (body starts with `{}`)",
+        .0.url,
+        .0.path,
+        .0.message,
+        .0.body
+    )]
+    DeserializationError(Box<DeserializationDetails>),
     /// HTTP transport error.
     #[error("HTTP transport error: `{0}`")]
-    TransportError(#[from] Transport),
+    TransportError(Box<Transport>),
     /// Invalid query parameter error.
     #[error("invalid query parameter: `{name}={value}`")]
     InvalidQueryParam {
@@ -36,6 +86,65 @@ pub enum ClientError {
         /// The value of the query parameter.
         value: String,
     },
+    /// Invalid query parameter value error, caught before any request is sent.
+    #[error("invalid query parameter value: `{name}={value}` ({reason})")]
+    InvalidQueryValue {
+        /// The name of the query parameter.
+        name: String,
+        /// The value of the query parameter.
+        value: String,
+        /// Why the value is invalid.
+        reason: String,
+    },
+    /// Rate-limited by the API (HTTP 429).
+    #[error("rate limited by the API until `{reset_at:?}`")]
+    RateLimited {
+        /// When the rate-limit window resets, if the API said.
+        reset_at: Option<DateTime<Utc>>,
+    },
+    /// Request that still failed after being retried.
+    #[error("request failed after {} attempts: {source}", attempts.len())]
+    RetriesExhausted {
+        /// Every failed attempt, in order, ending with the final one.
+        attempts: Vec<RetryAttempt>,
+        /// The error from the final attempt.
+        source: Box<ClientError>,
+    },
+    /// Invalid proxy error, caught when building a client.
+    #[error("invalid proxy: `{proxy}` ({reason})")]
+    InvalidProxy {
+        /// The proxy as given, with any credentials redacted.
+        proxy: String,
+        /// Why the proxy is invalid.
+        reason: String,
+    },
+    /// Builder options that the client being built doesn't support, caught when building a client.
+    #[error("unsupported client options: `{options}` ({reason})")]
+    UnsupportedOptions {
+        /// Names of the unsupported options, separated by commas.
+        options: String,
+        /// Why the options are unsupported.
+        reason: String,
+    },
+}
+
+/// Details of a [ClientError::DeserializationError].
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct DeserializationDetails {
+    /// URL of the request.
+    pub url: String,
+    /// Path to the value that failed to deserialize, such as `data[0].attributes.direction_id`.
+    pub path: String,
+    /// What went wrong.
+    pub message: String,
+    /// The start of the response body.
+    pub body: String,
+}
+
+impl From<Transport> for ClientError {
+    fn from(error: Transport) -> Self {
+        Self::TransportError(Box::new(error))
+    }
 }
 
 /// Custom error response from the MBTA API.
@@ -89,16 +198,131 @@ impl Display for APIError {
 
 impl StdError for APIError {}
 
+impl ClientError {
+    /// Create an error from an unsuccessful HTTP response.
+    ///
+    /// # Arguments
+    ///
+    /// * `response` - the unsuccessful response
+    pub(crate) fn from_response(response: &HttpResponse) -> Self {
+        let status = response.status;
+        if status == TOO_MANY_REQUESTS {
+            return Self::RateLimited {
+                reset_at: reset_at(&response.headers),
+            };
+        }
+        if status >= 500 {
+            return Self::ServerError {
+                status,
+                body: snippet(&response.body),
+            };
+        }
+        match (status, serde_json::from_str::<APIErrorResponse>(&response.body)) {
+            (400, Ok(errors)) => Self::BadRequest { errors },
+            (403, Ok(errors)) => Self::Forbidden { errors },
+            (404, Ok(errors)) => Self::NotFound { errors },
+            (_, Ok(errors)) => Self::ResponseError { status, errors },
+            (_, Err(_)) => Self::UnexpectedResponse {
+                status,
+                body: snippet(&response.body),
+            },
+        }
+    }
+
+    /// HTTP status code of the response that caused the error, if there was one.
+    pub fn status(&self) -> Option<u16> {
+        match self {
+            Self::ResponseError { status, .. } | Self::ServerError { status, .. } | Self::UnexpectedResponse { status, .. } => {
+                Some(*status)
+            }
+            Self::BadRequest { .. } => Some(400),
+            Self::Forbidden { .. } => Some(403),
+            Self::NotFound { .. } => Some(404),
+            Self::RateLimited { .. } => Some(TOO_MANY_REQUESTS),
+            Self::RetriesExhausted { source, .. } => source.status(),
+            _ => None,
+        }
+    }
+
+    /// Returns whether the same request might succeed if sent again later:
+    /// rate limiting, server errors, and failures to reach the API.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Self::IOError(_) | Self::TransportError(_) | Self::RateLimited { .. } | Self::ServerError { .. } => true,
+            Self::RetriesExhausted { source, .. } => source.is_retryable(),
+            _ => false,
+        }
+    }
+
+    /// Returns whether the API said the requested resource doesn't exist.
+    pub fn is_not_found(&self) -> bool {
+        self.status() == Some(404)
+    }
+}
+
+/// Deserialize a JSON response body, keeping the path to the value that failed if it doesn't match the data model.
+///
+/// # Arguments
+///
+/// * `url` - the URL the body came from
+/// * `body` - the response body
+pub(crate) fn deserialize<T: DeserializeOwned>(url: &str, body: &str) -> std::result::Result<T, ClientError> {
+    let error = |path: String, message: String| {
+        ClientError::DeserializationError(Box::new(DeserializationDetails {
+            url: url.into(),
+            path,
+            message,
+            body: snippet(body),
+        }))
+    };
+    let mut deserializer = serde_json::Deserializer::from_str(body);
+    let value = serde_path_to_error::deserialize(&mut deserializer).map_err(|e| {
+        let (path, message) = (e.path().to_string(), e.inner().to_string());
+        included_error(body, &path).unwrap_or((path, message))
+    });
+    let value = value.map_err(|(path, message)| error(path, message))?;
+    deserializer.end().map_err(|e| error(".".into(), e.to_string()))?;
+    Ok(value)
+}
+
+/// Find the path and message of a deserialization error within an included resource.
+/// Included resources are buffered to find their type before being deserialized, which hides the path within them,
+/// so the resource is deserialized again on its own.
+///
+/// # Arguments
+///
+/// * `body` - the response body
+/// * `path` - the path of the error within the body, such as `included[2]`
+fn included_error(body: &str, path: &str) -> Option<(String, String)> {
+    let index: usize = path.strip_prefix("included[")?.strip_suffix(']')?.parse().ok()?;
+    let value: Value = serde_json::from_str(body).ok()?;
+    let e = IncludedResource::decode(value.get("included")?.get(index)?.clone()).err()?;
+    Some((format!("{}.{}", path, e.path()), e.inner().to_string()))
+}
+
+/// The start of a response body, cut to at most [ERROR_SNIPPET_LENGTH] characters.
+///
+/// # Arguments
+///
+/// * `body` - the response body
+fn snippet(body: &str) -> String {
+    match body.char_indices().nth(ERROR_SNIPPET_LENGTH) {
+        Some((end, _)) => format!("{}...", &body[..end]),
+        None => body.into(),
+    }
+}
+
 impl From<RequestError> for ClientError {
     fn from(error: RequestError) -> Self {
         match error {
-            RequestError::Status(_, response) => {
-                let errs = response.into_json::<APIErrorResponse>();
-                match errs {
-                    Ok(errors) => Self::ResponseError { errors },
-                    Err(io) => Self::from(io),
-                }
-            }
+            RequestError::Status(status, response) => match response.into_string() {
+                Ok(body) => Self::from_response(&HttpResponse {
+                    status,
+                    headers: vec![],
+                    body,
+                }),
+                Err(io) => Self::from(io),
+            },
             RequestError::Transport(err) => Self::from(err),
         }
     }
@@ -108,8 +332,11 @@ impl From<RequestError> for ClientError {
 mod tests {
     use super::*;
 
+    use std::{io::ErrorKind, time::Duration};
+
+    use crate::AttemptOutcome;
+
     use rstest::*;
-    use std::io::ErrorKind;
     use ureq::Response;
 
     #[rstest]
@@ -129,12 +356,9 @@ mod tests {
     #[rstest]
     #[case::valid_error_text(
         "{\"errors\": [{\"status\": \"403\", \"code\": \"forbidden\"}], \"jsonapi\": {\"version\": \"1.0\"}}",
-        "HTTP response error: `APIErrorResponse { errors: [APIError { code: \"forbidden\", status: \"403\", detail: None, source: None }], jsonapi: APIVersion { version: \"1.0\" } }`",
-    )]
-    #[case::invalid_error_text(
-        "foobar",
-        "some kind of I/O error occured: `Failed to read JSON: expected ident at line 1 column 2`"
+        "not found: { errors: ({code: forbidden, status: 403}, ), api version: APIVersion { version: \"1.0\" }}"
     )]
+    #[case::invalid_error_text("foobar", "unexpected HTTP 404 response: `foobar`")]
     fn test_client_error_display_request_error(#[case] text: &str, #[case] expected: &str) {
         // Arrange
         let input = RequestError::Status(404, Response::new(404, "Page not found", text).unwrap());
@@ -147,6 +371,78 @@ mod tests {
         assert_eq!(actual, expected);
     }
 
+    #[rstest]
+    #[case::short("foobar", "foobar")]
+    #[case::long(&"a".repeat(300), &format!("{}...", "a".repeat(200)))]
+    #[case::multibyte(&"é".repeat(201), &format!("{}...", "é".repeat(200)))]
+    fn test_snippet(#[case] body: &str, #[case] expected: &str) {
+        // Arrange
+
+        // Act
+        let actual = snippet(body);
+
+        // Assert
+        assert_eq!(actual, expected);
+    }
+
+    #[rstest]
+    fn test_deserialize_error_path() {
+        // Arrange
+        let body = "{\"version\": 1}";
+
+        // Act
+        let actual = deserialize::<APIVersion>("https://foobar.com/status", body).expect_err("deserialized invalid body");
+
+        // Assert
+        assert_eq!(
+            actual.to_string(),
+            "failed to deserialize response from `https://foobar.com/status` at `version`: invalid type: integer `1`, expected a string at line 1 column 13 (body starts with `{\"version\": 1}`)"
+        );
+    }
+
+    #[rstest]
+    fn test_deserialize_error_path_in_included() {
+        // Arrange
+        let body = "{\"data\": [], \"jsonapi\": {\"version\": \"1.0\"}, \"included\": [{\"type\": \"shape\", \"id\": \"a\", \"attributes\": {\"polyline\": 1}}]}";
+
+        // Act
+        let actual =
+            deserialize::<crate::Response<Vec<crate::Shape>>>("https://foobar.com/shapes", body).expect_err("deserialized invalid body");
+
+        // Assert
+        if let ClientError::DeserializationError(details) = actual {
+            assert_eq!(details.path, "included[0].attributes.polyline");
+            assert_eq!(details.message, "invalid type: integer `1`, expected a string");
+        } else {
+            panic!("wrong error type");
+        }
+    }
+
+    #[rstest]
+    #[case::rate_limited(ClientError::RateLimited { reset_at: None }, Some(429), true)]
+    #[case::server_error(ClientError::ServerError { status: 503, body: "".into() }, Some(503), true)]
+    #[case::unexpected(ClientError::UnexpectedResponse { status: 404, body: "".into() }, Some(404), false)]
+    #[case::io(ClientError::from(IOError::new(ErrorKind::BrokenPipe, "test error")), None, true)]
+    #[case::query_param(ClientError::InvalidQueryParam { name: "foo".into(), value: "bar".into() }, None, false)]
+    #[case::retries_exhausted(
+        ClientError::RetriesExhausted { attempts: vec![], source: Box::new(ClientError::ServerError { status: 502, body: "".into() }) },
+        Some(502),
+        true
+    )]
+    fn test_client_error_status_and_retryable(
+        #[case] error: ClientError,
+        #[case] expected_status: Option<u16>,
+        #[case] expected_retryable: bool,
+    ) {
+        // Arrange
+
+        // Act
+        let actual = (error.status(), error.is_retryable());
+
+        // Assert
+        assert_eq!(actual, (expected_status, expected_retryable));
+    }
+
     #[rstest]
     fn test_client_error_display_invalid_query_param_error() {
         // Arrange
@@ -162,4 +458,97 @@ mod tests {
         // Assert
         assert_eq!(actual, expected);
     }
+
+    #[rstest]
+    fn test_client_error_display_invalid_query_value_error() {
+        // Arrange
+        let error = ClientError::InvalidQueryValue {
+            name: "filter[direction_id]".into(),
+            value: "2".into(),
+            reason: "must be 0 or 1".into(),
+        };
+        let expected = "invalid query parameter value: `filter[direction_id]=2` (must be 0 or 1)";
+
+        // Act
+        let actual = format!("{}", error);
+
+        // Assert
+        assert_eq!(actual, expected);
+    }
+
+    #[rstest]
+    fn test_client_error_display_invalid_proxy_error() {
+        // Arrange
+        let error = ClientError::InvalidProxy {
+            proxy: "foo://bar".into(),
+            reason: "unsupported scheme".into(),
+        };
+        let expected = "invalid proxy: `foo://bar` (unsupported scheme)";
+
+        // Act
+        let actual = format!("{}", error);
+
+        // Assert
+        assert_eq!(actual, expected);
+    }
+
+    #[rstest]
+    fn test_client_error_display_unsupported_options_error() {
+        // Arrange
+        let error = ClientError::UnsupportedOptions {
+            options: "retry_policy,cache".into(),
+            reason: "only a blocking client supports them".into(),
+        };
+        let expected = "unsupported client options: `retry_policy,cache` (only a blocking client supports them)";
+
+        // Act
+        let actual = format!("{}", error);
+
+        // Assert
+        assert_eq!(actual, expected);
+    }
+
+    #[rstest]
+    fn test_client_error_from_rate_limited_response() {
+        // Arrange
+        let response = HttpResponse {
+            status: 429,
+            headers: vec![("x-ratelimit-reset".into(), "1650000000".into())],
+            body: "".into(),
+        };
+
+        // Act
+        let actual = ClientError::from_response(&response);
+
+        // Assert
+        match actual {
+            ClientError::RateLimited { reset_at } => assert_eq!(reset_at.map(|r| r.timestamp()), Some(1_650_000_000)),
+            _ => panic!("wrong error type"),
+        }
+    }
+
+    #[rstest]
+    fn test_client_error_display_retries_exhausted_error() {
+        // Arrange
+        let error = ClientError::RetriesExhausted {
+            attempts: vec![
+                RetryAttempt {
+                    outcome: AttemptOutcome::Status(503),
+                    backoff: Some(Duration::from_millis(100)),
+                },
+                RetryAttempt {
+                    outcome: AttemptOutcome::Status(429),
+                    backoff: None,
+                },
+            ],
+            source: Box::new(ClientError::RateLimited { reset_at: None }),
+        };
+        let expected = "request failed after 2 attempts: rate limited by the API until `None`";
+
+        // Act
+        let actual = format!("{}", error);
+
+        // Assert
+        assert_eq!(actual, expected);
+    }
 }
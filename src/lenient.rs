@@ -0,0 +1,162 @@
+//! Lenient decoding, which drops the items of a response that don't match the data model instead of failing the whole response.
+
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+use super::*;
+
+/// Item of a response's `data` or `included` array that was dropped because it didn't match the data model.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ItemDecodeError {
+    /// Position of the item in its array; the start of [ItemDecodeError::path] tells which array.
+    pub index: usize,
+    /// Resource ID of the item, if it has one.
+    pub id: Option<String>,
+    /// Path to the value that failed to deserialize, such as `data[3].attributes.latitude` or `included[0].attributes.name`.
+    pub path: String,
+    /// What went wrong.
+    pub message: String,
+}
+
+/// Deserialize a JSON response body, optionally dropping the items of its `data` and `included` arrays that don't match the data model.
+///
+/// Leniency only applies when the rest of the response is valid; anything else fails with the same error as strict decoding.
+/// A `data` item that isn't in an array, such as the resource of a single-resource endpoint, can't be dropped.
+///
+/// # Arguments
+///
+/// * `url` - the URL the body came from
+/// * `body` - the response body
+/// * `lenient` - whether to drop invalid items
+pub(crate) fn decode<T: DeserializeOwned>(
+    url: &str,
+    body: &str,
+    lenient: bool,
+) -> Result<(Response<T>, Vec<ItemDecodeError>), ClientError> {
+    let strict_error = match deserialize(url, body) {
+        Ok(parsed) => return Ok((parsed, vec![])),
+        Err(e) if lenient => e,
+        Err(e) => return Err(e),
+    };
+    let mut value: Value = match serde_json::from_str(body) {
+        Ok(value) => value,
+        Err(_) => return Err(strict_error),
+    };
+    let mut errors = vec![];
+    if let Some(items) = value.get_mut("data").and_then(Value::as_array_mut) {
+        // Decoding the item on its own as a one-item `data` array tells whether it matches the item type.
+        retain_valid(items, "data", &mut errors, |item| {
+            let single = Value::Array(vec![item.clone()]);
+            serde_path_to_error::deserialize::<_, T>(single).map(|_| ()).map_err(|e| {
+                let path = e.path().to_string();
+                (path.strip_prefix("[0]").unwrap_or(&path).to_string(), e.into_inner().to_string())
+            })
+        });
+    }
+    if let Some(items) = value.get_mut("included").and_then(Value::as_array_mut) {
+        retain_valid(items, "included", &mut errors, |item| {
+            IncludedResource::decode(item.clone())
+                .map(|_| ())
+                .map_err(|e| (format!(".{}", e.path()), e.into_inner().to_string()))
+        });
+    }
+    match serde_json::from_value(value) {
+        Ok(parsed) => Ok((parsed, errors)),
+        Err(_) => Err(strict_error),
+    }
+}
+
+/// Drop the items of an array that fail to decode, recording an error for each.
+///
+/// # Arguments
+///
+/// * `items` - the items of the array
+/// * `array` - the name of the array, such as `data`
+/// * `errors` - where to record the errors
+/// * `check` - decodes an item, giving the path within the item and the message of any error
+fn retain_valid<F: Fn(&Value) -> Result<(), (String, String)>>(
+    items: &mut Vec<Value>,
+    array: &str,
+    errors: &mut Vec<ItemDecodeError>,
+    check: F,
+) {
+    let mut index = 0;
+    items.retain(|item| {
+        let kept = match check(item) {
+            Ok(()) => true,
+            Err((path, message)) => {
+                errors.push(ItemDecodeError {
+                    index,
+                    id: item.get("id").and_then(Value::as_str).map(String::from),
+                    path: format!("{}[{}]{}", array, index, path),
+                    message,
+                });
+                false
+            }
+        };
+        index += 1;
+        kept
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use rstest::*;
+
+    const BODY: &str = "{\"data\": [{\"type\": \"shape\", \"id\": \"a\", \"attributes\": {\"polyline\": \"abc\"}}, {\"type\": \"shape\", \"id\": \"b\", \"attributes\": {\"polyline\": 1}}], \"jsonapi\": {\"version\": \"1.0\"}}";
+
+    #[rstest]
+    fn test_decode_lenient() {
+        // Arrange
+        let expected = ItemDecodeError {
+            index: 1,
+            id: Some("b".into()),
+            path: "data[1].attributes.polyline".into(),
+            message: "invalid type: integer `1`, expected a string".into(),
+        };
+
+        // Act
+        let (response, errors) = decode::<Vec<Shape>>("https://foobar.com/shapes", BODY, true).expect("failed to decode");
+
+        // Assert
+        assert_eq!(response.data.len(), 1);
+        assert_eq!(response.data[0].id, "a");
+        assert_eq!(errors, vec![expected]);
+    }
+
+    #[rstest]
+    fn test_decode_lenient_included() {
+        // Arrange
+        let body = "{\"data\": [{\"type\": \"shape\", \"id\": \"a\", \"attributes\": {\"polyline\": \"abc\"}}], \"jsonapi\": {\"version\": \"1.0\"}, \"included\": [{\"type\": \"route\", \"id\": \"Red\", \"attributes\": {\"type\": 1}}, {\"type\": \"shape\", \"id\": \"b\", \"attributes\": {\"polyline\": \"def\"}}]}";
+
+        // Act
+        let (response, errors) = decode::<Vec<Shape>>("https://foobar.com/shapes", body, true).expect("failed to decode");
+
+        // Assert
+        let included = response.included.expect("no included resources");
+        assert_eq!(included.len(), 1);
+        assert_eq!(included[0].id(), "b");
+        assert_eq!(errors.len(), 1);
+        assert_eq!((errors[0].index, errors[0].id.as_deref()), (0, Some("Red")));
+        assert_eq!(errors[0].path, "included[0].attributes");
+    }
+
+    #[rstest]
+    #[case::strict(BODY, false)]
+    #[case::single_resource(
+        "{\"data\": {\"type\": \"shape\", \"id\": \"b\", \"attributes\": {\"polyline\": 1}}, \"jsonapi\": {\"version\": \"1.0\"}}",
+        true
+    )]
+    #[case::invalid_json("foobar", true)]
+    fn test_decode_fails(#[case] body: &str, #[case] lenient: bool) {
+        // Arrange
+
+        // Act
+        let actual = decode::<Vec<Shape>>("https://foobar.com/shapes", body, lenient);
+
+        // Assert
+        assert!(matches!(actual, Err(ClientError::DeserializationError(_))));
+    }
+}
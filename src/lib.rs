@@ -15,11 +15,41 @@
 )]
 #![doc = include_str!("../README.md")]
 
+#[cfg(feature = "async")]
+pub mod async_client;
+#[cfg(feature = "async")]
+pub use async_client::*;
+pub mod builder;
+pub use builder::*;
+pub mod cache;
+pub use cache::*;
 pub mod client;
 pub use client::*;
+pub mod conditional;
+pub use conditional::*;
+pub mod drift;
+pub use drift::*;
+pub mod endpoint;
+pub use endpoint::*;
 pub mod error;
 pub use error::*;
+pub mod lenient;
+pub use lenient::*;
+pub mod live;
 #[cfg(feature = "map")]
 pub mod map;
+pub use live::*;
 pub mod models;
 pub use models::*;
+pub mod pagination;
+pub use pagination::*;
+pub mod query;
+pub use query::*;
+pub mod rate_limit;
+pub use rate_limit::*;
+pub mod retry;
+pub use retry::*;
+pub mod streaming;
+pub use streaming::*;
+pub mod transport;
+pub use transport::*;
@@ -0,0 +1,497 @@
+//! Materialized live state built from streams of server-sent events.
+
+use std::{
+    collections::{BTreeSet, HashMap},
+    fmt::Debug,
+    hash::Hash,
+    sync::{
+        mpsc::{channel, Receiver, Sender},
+        Mutex, RwLock,
+    },
+};
+
+use super::*;
+
+/// Data model that can be kept in a [LiveStore], indexed by its ID, such as a [VehicleId], and by the routes, stops, and trips it refers to.
+pub trait LiveModel: Clone + Identified<Id: Debug + Eq + Hash + Ord + Clone> {
+    /// ID of the data model.
+    fn id(&self) -> &Self::Id;
+
+    /// IDs of the routes the data model refers to.
+    fn route_ids(&self) -> Vec<RouteId>;
+
+    /// IDs of the stops the data model refers to.
+    fn stop_ids(&self) -> Vec<StopId>;
+
+    /// IDs of the trips the data model refers to.
+    fn trip_ids(&self) -> Vec<TripId>;
+}
+
+impl LiveModel for Vehicle {
+    fn id(&self) -> &VehicleId {
+        &self.id
+    }
+
+    fn route_ids(&self) -> Vec<RouteId> {
+        self.route_id().into_iter().collect()
+    }
+
+    fn stop_ids(&self) -> Vec<StopId> {
+        self.stop_id().into_iter().collect()
+    }
+
+    fn trip_ids(&self) -> Vec<TripId> {
+        self.trip_id().into_iter().collect()
+    }
+}
+
+/// Predictions have no ID type of their own, since their IDs are made up of the IDs of what they predict.
+impl LiveModel for Prediction {
+    fn id(&self) -> &String {
+        &self.id
+    }
+
+    fn route_ids(&self) -> Vec<RouteId> {
+        self.route_id().into_iter().collect()
+    }
+
+    fn stop_ids(&self) -> Vec<StopId> {
+        self.stop_id().into_iter().collect()
+    }
+
+    fn trip_ids(&self) -> Vec<TripId> {
+        self.trip_id().into_iter().collect()
+    }
+}
+
+/// Alerts refer to routes, stops, and trips through their informed entities rather than their relationships.
+impl LiveModel for Alert {
+    fn id(&self) -> &AlertId {
+        &self.id
+    }
+
+    fn route_ids(&self) -> Vec<RouteId> {
+        informed_ids(self, |e| e.route.as_ref())
+    }
+
+    fn stop_ids(&self) -> Vec<StopId> {
+        informed_ids(self, |e| e.stop.as_ref())
+    }
+
+    fn trip_ids(&self) -> Vec<TripId> {
+        informed_ids(self, |e| e.trip.as_ref())
+    }
+}
+
+/// Helper function for collecting the distinct IDs of one kind from the informed entities of an alert.
+///
+/// # Arguments
+///
+/// * `alert` - the alert
+/// * `id` - picks the ID out of an informed entity
+fn informed_ids<'a, Id: Ord + Clone + 'a>(alert: &'a Alert, id: fn(&'a InformedEntity) -> Option<&'a Id>) -> Vec<Id> {
+    let ids: BTreeSet<&Id> = alert.attributes.informed_entity.iter().filter_map(id).collect();
+    ids.into_iter().cloned().collect()
+}
+
+/// Change made to a [LiveStore] by an event, as sent to subscribers.
+#[derive(Debug, PartialEq, Clone)]
+pub enum Change<T> {
+    /// Everything was replaced with the given data models.
+    Reset(Vec<T>),
+    /// A data model was added.
+    Added(T),
+    /// A data model was replaced with a new version.
+    Updated {
+        /// The previous version.
+        old: T,
+        /// The new version.
+        new: T,
+    },
+    /// A data model was removed.
+    Removed(T),
+}
+
+/// Consistent, point-in-time view of the data models in a [LiveStore].
+#[derive(Debug, PartialEq, Clone)]
+pub struct Snapshot<T: LiveModel> {
+    /// Data models by ID.
+    items: HashMap<T::Id, T>,
+    /// IDs of the data models referring to each route.
+    by_route: HashMap<RouteId, BTreeSet<T::Id>>,
+    /// IDs of the data models referring to each stop.
+    by_stop: HashMap<StopId, BTreeSet<T::Id>>,
+    /// IDs of the data models referring to each trip.
+    by_trip: HashMap<TripId, BTreeSet<T::Id>>,
+    /// Number of changes applied so far.
+    version: u64,
+}
+
+impl<T: LiveModel> Default for Snapshot<T> {
+    fn default() -> Self {
+        Self {
+            items: HashMap::new(),
+            by_route: HashMap::new(),
+            by_stop: HashMap::new(),
+            by_trip: HashMap::new(),
+            version: 0,
+        }
+    }
+}
+
+impl<T: LiveModel> Snapshot<T> {
+    /// Returns the data model with the given ID, if present.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - the ID of the data model
+    pub fn get(&self, id: impl Into<T::Id>) -> Option<&T> {
+        self.items.get(&id.into())
+    }
+
+    /// Number of data models.
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    /// Returns whether there are no data models.
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Number of changes applied to the store when the snapshot was taken.
+    pub fn version(&self) -> u64 {
+        self.version
+    }
+
+    /// Iterate over all data models, in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.items.values()
+    }
+
+    /// Returns the data models referring to a route, ordered by ID.
+    ///
+    /// # Arguments
+    ///
+    /// * `route_id` - the ID of the route
+    pub fn by_route(&self, route_id: impl Into<RouteId>) -> Vec<&T> {
+        self.lookup(&self.by_route, &route_id.into())
+    }
+
+    /// Returns the data models referring to a stop, ordered by ID.
+    ///
+    /// # Arguments
+    ///
+    /// * `stop_id` - the ID of the stop
+    pub fn by_stop(&self, stop_id: impl Into<StopId>) -> Vec<&T> {
+        self.lookup(&self.by_stop, &stop_id.into())
+    }
+
+    /// Returns the data models referring to a trip, ordered by ID.
+    ///
+    /// # Arguments
+    ///
+    /// * `trip_id` - the ID of the trip
+    pub fn by_trip(&self, trip_id: impl Into<TripId>) -> Vec<&T> {
+        self.lookup(&self.by_trip, &trip_id.into())
+    }
+
+    /// Look up the data models listed under a key of an index.
+    fn lookup<K: Eq + Hash>(&self, index: &HashMap<K, BTreeSet<T::Id>>, key: &K) -> Vec<&T> {
+        match index.get(key) {
+            Some(ids) => ids.iter().filter_map(|id| self.items.get(id)).collect(),
+            None => vec![],
+        }
+    }
+
+    /// Insert a data model, replacing and returning any previous version.
+    fn insert(&mut self, item: T) -> Option<T> {
+        let id = item.id().clone();
+        let old = self.remove(&id);
+        index(&mut self.by_route, item.route_ids(), &id);
+        index(&mut self.by_stop, item.stop_ids(), &id);
+        index(&mut self.by_trip, item.trip_ids(), &id);
+        self.items.insert(id, item);
+        old
+    }
+
+    /// Remove and return a data model, if present.
+    fn remove(&mut self, id: &T::Id) -> Option<T> {
+        let old = self.items.remove(id)?;
+        unindex(&mut self.by_route, old.route_ids(), id);
+        unindex(&mut self.by_stop, old.stop_ids(), id);
+        unindex(&mut self.by_trip, old.trip_ids(), id);
+        Some(old)
+    }
+
+    /// Apply an event, returning the resulting change if anything changed.
+    fn apply(&mut self, event: Event<T>) -> Option<Change<T>> {
+        let change = match event {
+            Event::Reset(items) => {
+                self.items.clear();
+                self.by_route.clear();
+                self.by_stop.clear();
+                self.by_trip.clear();
+                for item in items.iter().cloned() {
+                    self.insert(item);
+                }
+                Change::Reset(items)
+            }
+            Event::Add(item) | Event::Update(item) => match self.insert(item.clone()) {
+                Some(old) => Change::Updated { old, new: item },
+                None => Change::Added(item),
+            },
+            Event::Remove(removed) => Change::Removed(self.remove(&removed.id)?),
+            Event::KeepAlive => return None,
+        };
+        self.version += 1;
+        Some(change)
+    }
+}
+
+/// Helper function for listing a data model under each of the given keys of an index.
+///
+/// # Arguments
+///
+/// * `index` - the index
+/// * `keys` - the keys the data model refers to
+/// * `id` - the ID of the data model
+fn index<K: Eq + Hash, Id: Ord + Clone>(index: &mut HashMap<K, BTreeSet<Id>>, keys: Vec<K>, id: &Id) {
+    for key in keys {
+        index.entry(key).or_default().insert(id.clone());
+    }
+}
+
+/// Helper function for unlisting a data model from each of the given keys of an index, dropping keys left empty.
+///
+/// # Arguments
+///
+/// * `index` - the index
+/// * `keys` - the keys the data model referred to
+/// * `id` - the ID of the data model
+fn unindex<K: Eq + Hash, Id: Ord>(index: &mut HashMap<K, BTreeSet<Id>>, keys: Vec<K>, id: &Id) {
+    for key in keys {
+        if let Some(ids) = index.get_mut(&key) {
+            ids.remove(id);
+            if ids.is_empty() {
+                index.remove(&key);
+            }
+        }
+    }
+}
+
+/// Thread-safe store of the current data models from a stream of events, such as one from [Client::stream_vehicles].
+///
+/// Events are applied with [LiveStore::apply]; readers get consistent [Snapshot]s with indexes by route, stop, and trip,
+/// and subscribers are sent every [Change] in the order it was applied.
+///
+/// ```no_run
+/// # use mbta_rs::*;
+/// #
+/// let client = Client::without_key();
+/// let store = LiveStore::new();
+/// if let Ok(stream) = client.stream_vehicles(&[("filter[route]", "Red")]) {
+///     for event in stream.flatten() {
+///         store.apply(event);
+///         println!("{} vehicles on the Red Line", store.snapshot().by_route("Red").len());
+///     }
+/// }
+/// ```
+#[derive(Debug)]
+pub struct LiveStore<T: LiveModel> {
+    /// Current state.
+    state: RwLock<Snapshot<T>>,
+    /// Senders for every subscriber that hasn't hung up.
+    subscribers: Mutex<Vec<Sender<Change<T>>>>,
+}
+
+impl<T: LiveModel> Default for LiveStore<T> {
+    fn default() -> Self {
+        Self {
+            state: RwLock::new(Snapshot::default()),
+            subscribers: Mutex::new(Vec::new()),
+        }
+    }
+}
+
+impl<T: LiveModel> LiveStore<T> {
+    /// Create an empty [LiveStore].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Apply an event from a stream, notifying subscribers if anything changed.
+    ///
+    /// Adds of existing data models are treated as updates and updates of unknown data models are treated as adds,
+    /// so the store stays correct if events are missed; removes of unknown data models and keep-alives change nothing.
+    ///
+    /// # Arguments
+    ///
+    /// * `event` - the event to apply
+    pub fn apply(&self, event: Event<T>) {
+        let mut state = self.state.write().unwrap_or_else(|e| e.into_inner());
+        if let Some(change) = state.apply(event) {
+            let mut subscribers = self.subscribers.lock().unwrap_or_else(|e| e.into_inner());
+            subscribers.retain(|subscriber| subscriber.send(change.clone()).is_ok());
+        }
+    }
+
+    /// Take a consistent snapshot of the current data models.
+    pub fn snapshot(&self) -> Snapshot<T> {
+        self.state.read().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// Returns the current version of the data model with the given ID, if present.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - the ID of the data model
+    pub fn get(&self, id: impl Into<T::Id>) -> Option<T> {
+        self.state.read().unwrap_or_else(|e| e.into_inner()).get(id).cloned()
+    }
+
+    /// Number of data models currently in the store.
+    pub fn len(&self) -> usize {
+        self.state.read().unwrap_or_else(|e| e.into_inner()).len()
+    }
+
+    /// Returns whether the store currently has no data models.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Subscribe to every change applied from now on.
+    /// The subscription ends when the returned receiver is dropped.
+    pub fn subscribe(&self) -> Receiver<Change<T>> {
+        let (sender, receiver) = channel();
+        self.subscribers.lock().unwrap_or_else(|e| e.into_inner()).push(sender);
+        receiver
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use rstest::*;
+
+    fn vehicle(id: &str, route: &str, stop: &str, trip: &str) -> Vehicle {
+        serde_json::from_str(&format!(
+            "{{\"type\": \"vehicle\", \"id\": \"{}\", \"attributes\": {{\"updated_at\": \"2022-04-02T15:14:06-04:00\", \"speed\": null, \"occupancy_status\": null, \"longitude\": -71.0, \"latitude\": 42.3, \"label\": \"1\", \"direction_id\": 0, \"current_stop_sequence\": 1, \"current_status\": \"STOPPED_AT\", \"bearing\": 90}}, \"relationships\": {{\"route\": {{\"data\": {{\"type\": \"route\", \"id\": \"{}\"}}}}, \"stop\": {{\"data\": {{\"type\": \"stop\", \"id\": \"{}\"}}}}, \"trip\": {{\"data\": {{\"type\": \"trip\", \"id\": \"{}\"}}}}}}}}",
+            id, route, stop, trip
+        ))
+        .expect("invalid vehicle")
+    }
+
+    fn removal(id: &str) -> Event<Vehicle> {
+        Event::Remove(RelationshipAtom {
+            relationship_type: "vehicle".into(),
+            id: id.into(),
+        })
+    }
+
+    fn ids(items: Vec<&Vehicle>) -> Vec<&str> {
+        items.into_iter().map(|item| item.id.as_str()).collect()
+    }
+
+    #[rstest]
+    fn test_apply_events() {
+        // Arrange
+        let store = LiveStore::new();
+
+        // Act
+        store.apply(Event::Reset(vec![vehicle("a", "Red", "1", "t1"), vehicle("b", "Red", "2", "t2")]));
+        store.apply(Event::Add(vehicle("c", "Blue", "3", "t3")));
+        store.apply(Event::Update(vehicle("b", "Orange", "2", "t2")));
+        store.apply(removal("a"));
+        store.apply(removal("z"));
+        store.apply(Event::KeepAlive);
+
+        // Assert
+        let snapshot = store.snapshot();
+        assert_eq!(snapshot.len(), 2);
+        assert_eq!(snapshot.version(), 4);
+        assert!(snapshot.get("a").is_none());
+        assert_eq!(store.get("b").and_then(|b| b.route_id().map(String::from)), Some("Orange".into()));
+        assert!(snapshot.by_route("Red").is_empty());
+        assert_eq!(ids(snapshot.by_route("Orange")), vec!["b"]);
+        assert_eq!(ids(snapshot.by_stop("3")), vec!["c"]);
+        assert_eq!(ids(snapshot.by_trip("t2")), vec!["b"]);
+        assert_eq!(store.get(VehicleId::new("c")).map(|c| c.route_ids()), Some(vec![RouteId::new("Blue")]));
+    }
+
+    #[rstest]
+    fn test_reset_replaces_everything() {
+        // Arrange
+        let store = LiveStore::new();
+        store.apply(Event::Add(vehicle("a", "Red", "1", "t1")));
+
+        // Act
+        store.apply(Event::Reset(vec![vehicle("b", "Red", "2", "t2")]));
+
+        // Assert
+        let snapshot = store.snapshot();
+        assert_eq!(snapshot.iter().map(|item| item.id.as_str()).collect::<Vec<_>>(), vec!["b"]);
+        assert_eq!(ids(snapshot.by_route("Red")), vec!["b"]);
+        assert!(snapshot.by_stop("1").is_empty());
+    }
+
+    #[rstest]
+    fn test_snapshot_is_consistent() {
+        // Arrange
+        let store = LiveStore::new();
+        store.apply(Event::Add(vehicle("a", "Red", "1", "t1")));
+
+        // Act
+        let snapshot = store.snapshot();
+        store.apply(Event::Add(vehicle("b", "Red", "2", "t2")));
+
+        // Assert
+        assert_eq!(ids(snapshot.by_route("Red")), vec!["a"]);
+        assert_eq!(store.len(), 2);
+    }
+
+    #[rstest]
+    fn test_subscribe() {
+        // Arrange
+        let store = LiveStore::new();
+        let changes = store.subscribe();
+        let dropped = store.subscribe();
+        drop(dropped);
+
+        // Act
+        store.apply(Event::Add(vehicle("a", "Red", "1", "t1")));
+        store.apply(Event::Add(vehicle("a", "Red", "2", "t1")));
+        store.apply(removal("a"));
+        store.apply(Event::KeepAlive);
+
+        // Assert
+        let changes: Vec<Change<Vehicle>> = changes.try_iter().collect();
+        assert_eq!(changes.len(), 3);
+        assert!(matches!(&changes[0], Change::Added(item) if item.stop_id().as_deref() == Some("1")));
+        assert!(
+            matches!(&changes[1], Change::Updated { old, new } if old.stop_id().as_deref() == Some("1") && new.stop_id().as_deref() == Some("2"))
+        );
+        assert!(matches!(&changes[2], Change::Removed(item) if item.id == "a"));
+        assert_eq!(store.subscribers.lock().expect("poisoned lock").len(), 1);
+    }
+
+    #[rstest]
+    fn test_alert_informed_entities() {
+        // Arrange
+        let alert: Alert = serde_json::from_str(
+            "{\"type\": \"alert\", \"id\": \"1\", \"attributes\": {\"url\": null, \"updated_at\": \"2022-04-02T15:14:06-04:00\", \"timeframe\": null, \"short_header\": \"\", \"severity\": 1, \"service_effect\": \"\", \"lifecycle\": \"NEW\", \"informed_entity\": [{\"trip\": null, \"stop\": \"70061\", \"route_type\": 1, \"route\": \"Red\", \"facility\": null, \"direction_id\": null, \"activities\": [\"BOARD\"]}, {\"trip\": null, \"stop\": \"70063\", \"route_type\": 1, \"route\": \"Red\", \"facility\": null, \"direction_id\": null, \"activities\": [\"BOARD\"]}], \"header\": \"\", \"effect\": \"DELAY\", \"description\": null, \"created_at\": \"2022-04-02T15:14:06-04:00\", \"cause\": \"UNKNOWN_CAUSE\", \"banner\": null, \"active_period\": []}}",
+        )
+        .expect("invalid alert");
+
+        // Act
+        let route_ids = alert.route_ids();
+        let stop_ids = alert.stop_ids();
+        let trip_ids = alert.trip_ids();
+
+        // Assert
+        assert_eq!(route_ids, vec!["Red"]);
+        assert_eq!(stop_ids, vec!["70061", "70063"]);
+        assert!(trip_ids.is_empty());
+    }
+}
@@ -9,7 +9,7 @@ use super::*;
 pub type Alerts = Vec<Alert>;
 
 /// An active or upcoming system alert.
-pub type Alert = Resource<AlertAttributes>;
+pub type Alert = Resource<AlertAttributes, AlertId>;
 
 /// Attributes for an alert.
 #[derive(Deserialize, Serialize, Debug, PartialEq, Clone)]
@@ -47,8 +47,32 @@ pub struct AlertAttributes {
     pub active_period: Vec<ActivePeriod>,
     /// Entities affected by an alert.
     pub informed_entity: Vec<InformedEntity>,
+    /// URL of an image to show with the alert, such as a map of a detour.
+    #[serde(default)]
+    pub image: Option<String>,
+    /// Text describing the image, for accessibility.
+    #[serde(default)]
+    pub image_alternative_text: Option<String>,
+    /// How certain the end of the alert's active periods is, if reported.
+    #[serde(default)]
+    pub duration_certainty: Option<DurationCertainty>,
+    /// More specific description of the cause than [AlertAttributes::cause].
+    #[serde(default)]
+    pub cause_detail: Option<String>,
+    /// More specific description of the effect than [AlertAttributes::effect].
+    #[serde(default)]
+    pub effect_detail: Option<String>,
 }
 
+model!(
+    model = Alert,
+    resource_type = "alert",
+    relationships = {
+        /// IDs of the facilities affected by the alert.
+        fn facility_ids() -> Vec<FacilityId> = "facilities";
+    }
+);
+
 /// Start and end dates for an active alert.
 #[derive(Deserialize, Serialize, Debug, PartialEq, Clone, Copy)]
 pub struct ActivePeriod {
@@ -65,23 +89,39 @@ pub struct ActivePeriod {
 #[derive(Deserialize, Serialize, Debug, PartialEq, Clone)]
 pub struct InformedEntity {
     /// ID of the affected trip.
-    pub trip: Option<String>,
+    pub trip: Option<TripId>,
     /// ID of the affected stop.
-    pub stop: Option<String>,
+    pub stop: Option<StopId>,
     /// Type of the affected route.
     pub route_type: Option<RouteType>,
     /// ID of the affected route.
-    pub route: Option<String>,
+    pub route: Option<RouteId>,
     /// ID of the affected facility.
-    pub facility: Option<String>,
+    pub facility: Option<FacilityId>,
     /// Direction ID of the affected trip.
     pub direction_id: Option<u8>,
     /// Activities affected by the alert.
     pub activities: Vec<Activity>,
 }
 
+/// How certain the end of an alert's active periods is.
+#[derive(Deserialize, Serialize, Debug, PartialEq, Clone)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum DurationCertainty {
+    /// The end is known.
+    Known,
+    /// The end is estimated.
+    Estimated,
+    /// The end is unknown.
+    Unknown,
+    /// A value added to the API after this version of the crate, kept as is.
+    /// Named differently from the other enums' catch-alls since `UNKNOWN` is already a value.
+    #[serde(untagged)]
+    Unrecognized(String),
+}
+
 /// Whether an alert is a new or old, in effect or upcoming.
-#[derive(Deserialize, Serialize, Debug, PartialEq, Clone, Copy)]
+#[derive(Deserialize, Serialize, Debug, PartialEq, Clone)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum Lifecycle {
     /// A new alert.
@@ -92,10 +132,13 @@ pub enum Lifecycle {
     OngoingUpcoming,
     /// An upcoming alert.
     Upcoming,
+    /// A value added to the API after this version of the crate, kept as is.
+    #[serde(untagged)]
+    Unknown(String),
 }
 
 /// The effect of a problem on an affected entity.
-#[derive(Deserialize, Serialize, Debug, PartialEq, Clone, Copy)]
+#[derive(Deserialize, Serialize, Debug, PartialEq, Clone)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum Effect {
     /// Access issue.
@@ -162,10 +205,13 @@ pub enum Effect {
     TrackChange,
     /// Unknown effect.
     UnknownEffect,
+    /// A value added to the API after this version of the crate, kept as is.
+    #[serde(untagged)]
+    Unknown(String),
 }
 
 /// What is causing an alert.
-#[derive(Deserialize, Serialize, Debug, PartialEq, Clone, Copy)]
+#[derive(Deserialize, Serialize, Debug, PartialEq, Clone)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum Cause {
     /// A general accident.
@@ -254,10 +300,13 @@ pub enum Cause {
     UnknownCause,
     /// Weather.
     Weather,
+    /// A value added to the API after this version of the crate, kept as is.
+    #[serde(untagged)]
+    Unknown(String),
 }
 
 /// An activity affected by an alert.
-#[derive(Deserialize, Serialize, Debug, PartialEq, Clone, Copy)]
+#[derive(Deserialize, Serialize, Debug, PartialEq, Clone)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum Activity {
     /// Boarding a vehicle.
@@ -276,4 +325,7 @@ pub enum Activity {
     UsingEscalator,
     /// Using a wheelchair.
     UsingWheelchair,
+    /// A value added to the API after this version of the crate, kept as is.
+    #[serde(untagged)]
+    Unknown(String),
 }
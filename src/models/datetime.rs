@@ -258,6 +258,76 @@ pub mod mbta_date_format {
     }
 }
 
+/// Serialization and deserialization for the MBTA date format as a date without a timezone.
+pub mod naive_mbta_date_format {
+    use chrono::NaiveDate;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    use super::DATE_FORMAT;
+
+    /// Serialize an MBTA date without a timezone.
+    ///
+    /// # Arguments
+    ///
+    /// * `date` - the date
+    /// * `serializer` - the serializer
+    pub fn serialize<S: Serializer>(date: &NaiveDate, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&format!("{}", date.format(DATE_FORMAT)))
+    }
+
+    /// Attempt to deserialize an MBTA date without a timezone.
+    ///
+    /// # Arguments
+    ///
+    /// * `deserializer` - the deserializer
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<NaiveDate, D::Error> {
+        NaiveDate::parse_from_str(&String::deserialize(deserializer)?, DATE_FORMAT).map_err(serde::de::Error::custom)
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        use chrono::NaiveDate;
+        use rstest::*;
+        use serde_json::{Deserializer, Serializer};
+
+        #[fixture]
+        fn serializer() -> Serializer<Vec<u8>> {
+            Serializer::new(Vec::new())
+        }
+
+        #[rstest]
+        #[case::simple_case(NaiveDate::from_ymd_opt(2022, 5, 8).expect("invalid input"), "\"2022-05-08\"")]
+        fn test_serialize(mut serializer: Serializer<Vec<u8>>, #[case] input: NaiveDate, #[case] expected: &str) {
+            // Arrange
+
+            // Act
+            serialize(&input, &mut serializer).expect("failed to serialize");
+            let inner = serializer.into_inner();
+            let actual = std::str::from_utf8(&inner).expect("failed to convert to string");
+
+            // Assert
+            assert_eq!(actual, expected);
+        }
+
+        #[rstest]
+        #[case::valid_format("\"2022-05-08\"", NaiveDate::from_ymd_opt(2022, 5, 8).expect("invalid input"))]
+        #[should_panic = "failed to deserialize"]
+        #[case::invalid_format("\"2022 05 08\"", NaiveDate::from_ymd_opt(2022, 5, 8).expect("invalid input"))]
+        fn test_deserialize(#[case] input: &str, #[case] expected: NaiveDate) {
+            // Arrange
+            let mut deserializer = Deserializer::from_str(input);
+
+            // Act
+            let actual = deserialize(&mut deserializer).expect("failed to deserialize");
+
+            // Assert
+            assert_eq!(actual, expected);
+        }
+    }
+}
+
 /// Serialization and deserialization for an optional MBTA date format.
 pub mod optional_mbta_date_format {
     use chrono::{Date, DateTime, FixedOffset};
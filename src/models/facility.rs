@@ -11,7 +11,7 @@ use super::*;
 pub type Facilities = Vec<Facility>;
 
 /// An amenity at a station stop such as an elevator, escalator, parking lot, or bike storage.
-pub type Facility = Resource<FacilityAttributes>;
+pub type Facility = Resource<FacilityAttributes, FacilityId>;
 
 /// Attributes for a facilit.
 #[derive(Deserialize, Serialize, Debug, PartialEq, Clone)]
@@ -33,8 +33,17 @@ pub struct FacilityAttributes {
     pub longitude: Option<f64>,
 }
 
+model!(
+    model = Facility,
+    resource_type = "facility",
+    relationships = {
+        /// ID of the stop the facility is at, if present in the relationships.
+        fn stop_id() -> Option<StopId> = "stop";
+    }
+);
+
 /// The types of facilities.
-#[derive(Deserialize, Serialize, Debug, PartialEq, Clone, Copy)]
+#[derive(Deserialize, Serialize, Debug, PartialEq, Clone)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum FacilityType {
     /// Bike storage.
@@ -75,4 +84,7 @@ pub enum FacilityType {
     TaxiStand,
     /// Ticket window.
     TicketWindow,
+    /// A value added to the API after this version of the crate, kept as is.
+    #[serde(untagged)]
+    Unknown(String),
 }
@@ -0,0 +1,149 @@
+//! Strongly typed IDs for the resources of the V3 API.
+//!
+//! Each ID converts from a string, so string literals can still be passed wherever an ID is expected,
+//! but an ID of one resource type can't be passed where an ID of another is expected.
+
+use std::{
+    borrow::Borrow,
+    fmt::{Display, Formatter, Result as FmtResult},
+    ops::Deref,
+};
+
+use serde::{Deserialize, Serialize};
+
+/// Macro for declaring a resource ID, serialized as a plain string.
+macro_rules! resource_id {
+    (name=$name:ident, resource=$resource:literal) => {
+        #[doc = concat!("ID of ", $resource, ".")]
+        #[derive(Deserialize, Serialize, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Default)]
+        #[serde(transparent)]
+        pub struct $name(pub String);
+
+        impl $name {
+            #[doc = concat!("Create a [", stringify!($name), "] from a string.")]
+            ///
+            /// # Arguments
+            ///
+            /// * `id` - the ID as a string
+            pub fn new<S: Into<String>>(id: S) -> Self {
+                Self(id.into())
+            }
+
+            /// The ID as a string slice.
+            pub fn as_str(&self) -> &str {
+                &self.0
+            }
+
+            /// Convert the ID into its string.
+            pub fn into_inner(self) -> String {
+                self.0
+            }
+        }
+
+        impl Display for $name {
+            fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+                write!(f, "{}", self.0)
+            }
+        }
+
+        impl Deref for $name {
+            type Target = str;
+
+            fn deref(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl AsRef<str> for $name {
+            fn as_ref(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl Borrow<str> for $name {
+            fn borrow(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl From<String> for $name {
+            fn from(id: String) -> Self {
+                Self(id)
+            }
+        }
+
+        impl From<&str> for $name {
+            fn from(id: &str) -> Self {
+                Self(id.into())
+            }
+        }
+
+        impl From<&String> for $name {
+            fn from(id: &String) -> Self {
+                Self(id.clone())
+            }
+        }
+
+        impl From<&$name> for $name {
+            fn from(id: &$name) -> Self {
+                id.clone()
+            }
+        }
+
+        impl From<$name> for String {
+            fn from(id: $name) -> Self {
+                id.0
+            }
+        }
+
+        impl PartialEq<str> for $name {
+            fn eq(&self, other: &str) -> bool {
+                self.0 == other
+            }
+        }
+
+        impl PartialEq<&str> for $name {
+            fn eq(&self, other: &&str) -> bool {
+                self.0 == *other
+            }
+        }
+
+        impl PartialEq<String> for $name {
+            fn eq(&self, other: &String) -> bool {
+                &self.0 == other
+            }
+        }
+    };
+}
+
+resource_id!(name = AlertId, resource = "an alert");
+resource_id!(name = FacilityId, resource = "a facility, which is also the ID of its live facility data");
+resource_id!(name = LineId, resource = "a line");
+resource_id!(name = RouteId, resource = "a route");
+resource_id!(name = RoutePatternId, resource = "a route pattern");
+resource_id!(name = ServiceId, resource = "a service");
+resource_id!(name = ShapeId, resource = "a shape");
+resource_id!(name = StopId, resource = "a stop");
+resource_id!(name = TripId, resource = "a trip");
+resource_id!(name = VehicleId, resource = "a vehicle");
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use rstest::*;
+
+    #[rstest]
+    fn test_id_serde_transparent() {
+        // Arrange
+        let input = "\"place-sstat\"";
+
+        // Act
+        let id: StopId = serde_json::from_str(input).expect("failed to deserialize stop ID");
+
+        // Assert
+        assert_eq!(id, "place-sstat");
+        assert_eq!(id.to_string(), "place-sstat");
+        assert_eq!(serde_json::to_string(&id).expect("failed to serialize stop ID"), input);
+    }
+}
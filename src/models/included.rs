@@ -0,0 +1,395 @@
+//! Data models for side-loaded (included) resources in compound documents.
+
+use std::{borrow::Borrow, collections::HashMap};
+
+use serde::{de::Error as DeError, Deserialize, Deserializer, Serialize};
+use serde_json::Value;
+
+use super::*;
+
+/// A side-loaded resource from the `included` member of a compound document, typed by its JSON API resource type.
+///
+/// Resource types that the crate doesn't model yet are kept as [IncludedResource::Unknown] rather than failing the whole response.
+#[derive(Serialize, Debug, PartialEq, Clone)]
+#[serde(untagged)]
+pub enum IncludedResource {
+    /// An included alert.
+    Alert(Alert),
+    /// An included facility.
+    Facility(Facility),
+    /// An included line.
+    Line(Line),
+    /// An included live facility.
+    LiveFacility(LiveFacility),
+    /// An included prediction.
+    Prediction(Prediction),
+    /// An included route.
+    Route(Route),
+    /// An included route pattern.
+    RoutePattern(RoutePattern),
+    /// An included schedule.
+    Schedule(Schedule),
+    /// An included service.
+    Service(Service),
+    /// An included shape.
+    Shape(Shape),
+    /// An included stop.
+    Stop(Stop),
+    /// An included trip.
+    Trip(Trip),
+    /// An included vehicle.
+    Vehicle(Vehicle),
+    /// An included resource of a type that isn't modeled by this crate.
+    Unknown(Resource<Value>),
+}
+
+impl IncludedResource {
+    /// The JSON API resource type of the included resource.
+    pub fn resource_type(&self) -> &str {
+        match self {
+            Self::Alert(r) => &r.resource_type,
+            Self::Facility(r) => &r.resource_type,
+            Self::Line(r) => &r.resource_type,
+            Self::LiveFacility(r) => &r.resource_type,
+            Self::Prediction(r) => &r.resource_type,
+            Self::Route(r) => &r.resource_type,
+            Self::RoutePattern(r) => &r.resource_type,
+            Self::Schedule(r) => &r.resource_type,
+            Self::Service(r) => &r.resource_type,
+            Self::Shape(r) => &r.resource_type,
+            Self::Stop(r) => &r.resource_type,
+            Self::Trip(r) => &r.resource_type,
+            Self::Vehicle(r) => &r.resource_type,
+            Self::Unknown(r) => &r.resource_type,
+        }
+    }
+
+    /// The JSON API resource id of the included resource.
+    pub fn id(&self) -> &str {
+        match self {
+            Self::Alert(r) => &r.id,
+            Self::Facility(r) => &r.id,
+            Self::Line(r) => &r.id,
+            Self::LiveFacility(r) => &r.id,
+            Self::Prediction(r) => &r.id,
+            Self::Route(r) => &r.id,
+            Self::RoutePattern(r) => &r.id,
+            Self::Schedule(r) => &r.id,
+            Self::Service(r) => &r.id,
+            Self::Shape(r) => &r.id,
+            Self::Stop(r) => &r.id,
+            Self::Trip(r) => &r.id,
+            Self::Vehicle(r) => &r.id,
+            Self::Unknown(r) => &r.id,
+        }
+    }
+}
+
+impl IncludedResource {
+    /// Deserialize an included resource, keeping the path to the value that failed if it doesn't match the data model for its type.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - the included resource as JSON
+    pub(crate) fn decode(value: Value) -> Result<Self, serde_path_to_error::Error<serde_json::Error>> {
+        let resource_type = value.get("type").and_then(Value::as_str).unwrap_or_default().to_string();
+        let included = match resource_type.as_str() {
+            "alert" => Self::Alert(serde_path_to_error::deserialize(value)?),
+            "facility" => Self::Facility(serde_path_to_error::deserialize(value)?),
+            "line" => Self::Line(serde_path_to_error::deserialize(value)?),
+            "live_facility" => Self::LiveFacility(serde_path_to_error::deserialize(value)?),
+            "prediction" => Self::Prediction(serde_path_to_error::deserialize(value)?),
+            "route" => Self::Route(serde_path_to_error::deserialize(value)?),
+            "route_pattern" => Self::RoutePattern(serde_path_to_error::deserialize(value)?),
+            "schedule" => Self::Schedule(serde_path_to_error::deserialize(value)?),
+            "service" => Self::Service(serde_path_to_error::deserialize(value)?),
+            "shape" => Self::Shape(serde_path_to_error::deserialize(value)?),
+            "stop" => Self::Stop(serde_path_to_error::deserialize(value)?),
+            "trip" => Self::Trip(serde_path_to_error::deserialize(value)?),
+            "vehicle" => Self::Vehicle(serde_path_to_error::deserialize(value)?),
+            _ => Self::Unknown(serde_path_to_error::deserialize(value)?),
+        };
+        Ok(included)
+    }
+}
+
+impl<'de> Deserialize<'de> for IncludedResource {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = Value::deserialize(deserializer)?;
+        if value.get("type").and_then(Value::as_str).is_none() {
+            return Err(DeError::missing_field("type"));
+        }
+        Self::decode(value).map_err(DeError::custom)
+    }
+}
+
+/// Data models that can be pulled out of an [IncludedResource].
+pub trait Includable: Sized {
+    /// Returns a reference to the data model if the included resource is of this model's type.
+    ///
+    /// # Arguments
+    ///
+    /// * `included` - the included resource
+    fn from_included(included: &IncludedResource) -> Option<&Self>;
+}
+
+/// Macro for implementing [Includable] and conversions into [IncludedResource] for a data model.
+macro_rules! includable {
+    (model=$model:ident, variant=$variant:ident) => {
+        impl Includable for $model {
+            fn from_included(included: &IncludedResource) -> Option<&Self> {
+                match included {
+                    IncludedResource::$variant(r) => Some(r),
+                    _ => None,
+                }
+            }
+        }
+
+        impl From<$model> for IncludedResource {
+            fn from(value: $model) -> Self {
+                Self::$variant(value)
+            }
+        }
+    };
+}
+
+includable!(model = Alert, variant = Alert);
+includable!(model = Facility, variant = Facility);
+includable!(model = Line, variant = Line);
+includable!(model = LiveFacility, variant = LiveFacility);
+includable!(model = Prediction, variant = Prediction);
+includable!(model = Route, variant = Route);
+includable!(model = RoutePattern, variant = RoutePattern);
+includable!(model = Schedule, variant = Schedule);
+includable!(model = Service, variant = Service);
+includable!(model = Shape, variant = Shape);
+includable!(model = Stop, variant = Stop);
+includable!(model = Trip, variant = Trip);
+includable!(model = Vehicle, variant = Vehicle);
+
+/// Resolves relationships between data models into the related data models themselves.
+///
+/// A resolver can be built from the included resources of a compound document (see [Response::resolver])
+/// or from any local snapshot of data models, such as all the routes fetched earlier.
+///
+/// ```
+/// use mbta_rs::*;
+///
+/// fn print_route_names(trips: &Response<Trips>, routes: Routes) {
+///     let mut resolver = trips.resolver();
+///     resolver.extend(routes.into_iter().map(IncludedResource::from));
+///     for trip in &trips.data {
+///         if let Some(route) = resolver.resolve_relationship::<Route, _>(trip, "route") {
+///             println!("{} runs on {}", trip.id, route.attributes.long_name);
+///         }
+///     }
+/// }
+/// ```
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct Resolver {
+    /// Resources keyed by their JSON API resource type and id.
+    resources: HashMap<(String, String), IncludedResource>,
+}
+
+impl Resolver {
+    /// Create an empty [Resolver].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a resource to the resolver, replacing any resource with the same type and id.
+    ///
+    /// # Arguments
+    ///
+    /// * `resource` - the resource to add
+    pub fn insert<R: Into<IncludedResource>>(&mut self, resource: R) {
+        let resource = resource.into();
+        self.resources.insert((resource.resource_type().to_string(), resource.id().to_string()), resource);
+    }
+
+    /// Returns the number of resources in the resolver.
+    pub fn len(&self) -> usize {
+        self.resources.len()
+    }
+
+    /// Returns whether the resolver has no resources.
+    pub fn is_empty(&self) -> bool {
+        self.resources.is_empty()
+    }
+
+    /// Look up a resource by its JSON API resource type and id.
+    ///
+    /// # Arguments
+    ///
+    /// * `resource_type` - the JSON API resource type, such as `"route"`
+    /// * `id` - the JSON API resource id
+    pub fn get<T: Includable>(&self, resource_type: &str, id: &str) -> Option<&T> {
+        self.resources.get(&(resource_type.to_string(), id.to_string())).and_then(T::from_included)
+    }
+
+    /// Resolve a relationship's atomic data into the related data model.
+    ///
+    /// # Arguments
+    ///
+    /// * `atom` - the relationship's atomic data, with either a plain [String] or a strongly typed ID
+    pub fn resolve<T: Includable, Id: Borrow<str>>(&self, atom: &RelationshipAtom<Id>) -> Option<&T> {
+        self.get(&atom.relationship_type, atom.id.borrow())
+    }
+
+    /// Resolve a named relationship of a data model into the related data model.
+    ///
+    /// # Arguments
+    ///
+    /// * `resource` - the data model with the relationship
+    /// * `name` - the name of the relationship, such as `"route"` or `"stop"`
+    pub fn resolve_relationship<T: Includable, A>(&self, resource: &Resource<A, impl Sized>, name: &str) -> Option<&T> {
+        resource.relationship(name).and_then(|atom| self.resolve(atom))
+    }
+
+    /// Resolve all related data models of a named to-one or to-many relationship,
+    /// skipping any that aren't known to the resolver.
+    ///
+    /// # Arguments
+    ///
+    /// * `resource` - the data model with the relationship
+    /// * `name` - the name of the relationship, such as `"child_stops"`
+    pub fn resolve_all<T: Includable, A>(&self, resource: &Resource<A, impl Sized>, name: &str) -> Vec<&T> {
+        resource.relationship_atoms(name).iter().filter_map(|atom| self.resolve(atom)).collect()
+    }
+}
+
+impl FromIterator<IncludedResource> for Resolver {
+    fn from_iter<I: IntoIterator<Item = IncludedResource>>(iter: I) -> Self {
+        let mut resolver = Self::new();
+        resolver.extend(iter);
+        resolver
+    }
+}
+
+impl Extend<IncludedResource> for Resolver {
+    fn extend<I: IntoIterator<Item = IncludedResource>>(&mut self, iter: I) {
+        for resource in iter {
+            self.insert(resource);
+        }
+    }
+}
+
+impl<D> Response<D> {
+    /// Create a [Resolver] from the included resources of this response.
+    pub fn resolver(&self) -> Resolver {
+        self.included.iter().flatten().cloned().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use rstest::*;
+
+    #[rstest]
+    #[case::route(
+        "{\"type\": \"route\", \"id\": \"Red\", \"attributes\": {\"type\": 1, \"short_name\": \"\", \"long_name\": \"Red Line\", \"color\": \"DA291C\", \"text_color\": \"FFFFFF\", \"sort_order\": 10010, \"fare_class\": \"Rapid Transit\", \"description\": \"Rapid Transit\"}}",
+        "route",
+        "Red"
+    )]
+    #[case::shape(
+        "{\"type\": \"shape\", \"id\": \"931_0009\", \"attributes\": {\"polyline\": \"abc\"}}",
+        "shape",
+        "931_0009"
+    )]
+    #[case::unknown(
+        "{\"type\": \"occupancy\", \"id\": \"foo\", \"attributes\": {\"status\": \"FULL\"}}",
+        "occupancy",
+        "foo"
+    )]
+    fn test_included_resource_deserialize(#[case] input: &str, #[case] expected_type: &str, #[case] expected_id: &str) {
+        // Arrange
+
+        // Act
+        let actual: IncludedResource = serde_json::from_str(input).expect("failed to deserialize");
+
+        // Assert
+        assert_eq!(actual.resource_type(), expected_type);
+        assert_eq!(actual.id(), expected_id);
+        match expected_type {
+            "route" => assert!(matches!(actual, IncludedResource::Route(_))),
+            "shape" => assert!(matches!(actual, IncludedResource::Shape(_))),
+            _ => assert!(matches!(actual, IncludedResource::Unknown(_))),
+        }
+    }
+
+    #[rstest]
+    #[case::missing_type("{\"id\": \"foo\", \"attributes\": {}}")]
+    #[case::wrong_attributes("{\"type\": \"shape\", \"id\": \"foo\", \"attributes\": {\"foo\": \"bar\"}}")]
+    fn test_included_resource_deserialize_failure(#[case] input: &str) {
+        // Arrange
+
+        // Act
+        let actual = serde_json::from_str::<IncludedResource>(input);
+
+        // Assert
+        assert!(actual.is_err());
+    }
+
+    #[rstest]
+    fn test_included_resource_serialize_round_trip() {
+        // Arrange
+        let input = "{\"type\":\"shape\",\"id\":\"931_0009\",\"links\":null,\"attributes\":{\"polyline\":\"abc\"},\"relationships\":null}";
+        let included: IncludedResource = serde_json::from_str(input).expect("failed to deserialize");
+
+        // Act
+        let actual = serde_json::to_string(&included).expect("failed to serialize");
+
+        // Assert
+        assert_eq!(actual, input);
+    }
+
+    #[fixture]
+    fn resolver() -> Resolver {
+        let included: Vec<IncludedResource> = serde_json::from_str(
+            "[{\"type\": \"shape\", \"id\": \"foo\", \"attributes\": {\"polyline\": \"abc\"}}, \
+             {\"type\": \"occupancy\", \"id\": \"bar\", \"attributes\": {}}]",
+        )
+        .expect("failed to deserialize");
+        included.into_iter().collect()
+    }
+
+    #[rstest]
+    #[case::matching_type("shape", "foo", Some("abc"))]
+    #[case::missing_id("shape", "bar", None)]
+    #[case::other_type("occupancy", "bar", None)]
+    fn test_resolver_resolve(resolver: Resolver, #[case] resource_type: &str, #[case] id: &str, #[case] expected: Option<&str>) {
+        // Arrange
+        let atom = RelationshipAtom {
+            relationship_type: resource_type.into(),
+            id: ShapeId::new(id),
+        };
+
+        // Act
+        let actual = resolver.resolve::<Shape, _>(&atom).map(|shape| shape.attributes.polyline.as_str());
+
+        // Assert
+        assert_eq!(actual, expected);
+    }
+
+    #[rstest]
+    fn test_resolver_resolve_relationship(resolver: Resolver) {
+        // Arrange
+        let trip: Resource<Value> = serde_json::from_str(
+            "{\"type\": \"trip\", \"id\": \"t\", \"attributes\": {}, \
+             \"relationships\": {\"shape\": {\"data\": {\"type\": \"shape\", \"id\": \"foo\"}}, \"route\": {\"data\": null}}}",
+        )
+        .expect("failed to deserialize");
+
+        // Act
+        let shape = resolver.resolve_relationship::<Shape, _>(&trip, "shape");
+        let route = resolver.resolve_relationship::<Route, _>(&trip, "route");
+
+        // Assert
+        assert_eq!(shape.map(|s| s.id.as_str()), Some("foo"));
+        assert!(route.is_none());
+        assert_eq!(resolver.resolve_all::<Shape, _>(&trip, "shape").len(), 1);
+        assert_eq!(resolver.len(), 2);
+    }
+}
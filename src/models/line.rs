@@ -8,7 +8,9 @@ use super::*;
 pub type Lines = Vec<Line>;
 
 /// A combination of routes.
-pub type Line = Resource<LineAttributes>;
+pub type Line = Resource<LineAttributes, LineId>;
+
+model!(model = Line, resource_type = "line", relationships = {});
 
 /// Attributes for a line.
 #[derive(Deserialize, Serialize, Debug, PartialEq, Clone)]
@@ -10,7 +10,7 @@ use super::*;
 pub type LiveFacilities = Vec<LiveFacility>;
 
 /// Live data about a given facility.
-pub type LiveFacility = Resource<LiveFacilityAttributes>;
+pub type LiveFacility = Resource<LiveFacilityAttributes, FacilityId>;
 
 /// Attributes for a live facility.
 #[derive(Debug, PartialEq, Clone, Deserialize, Serialize)]
@@ -22,6 +22,15 @@ pub struct LiveFacilityAttributes {
     pub properties: Vec<LiveFacilityProperty>,
 }
 
+model!(
+    model = LiveFacility,
+    resource_type = "live_facility",
+    relationships = {
+        /// ID of the facility the live data is for, if present in the relationships.
+        fn facility_id() -> Option<FacilityId> = "facility";
+    }
+);
+
 /// Properties for a live facility.
 #[derive(Debug, PartialEq, Clone, Deserialize, Serialize)]
 pub struct LiveFacilityProperty {
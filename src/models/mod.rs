@@ -1,11 +1,42 @@
 //! Data models for the V3 API.
 
+/// Macro for implementing [Model] for a data model along with accessors for the IDs of its relationships,
+/// so the relationships a data model knows about are always the ones it has accessors for.
+macro_rules! model {
+    (model=$model:ident, resource_type=$resource_type:literal, relationships={$($(#[$meta:meta])* fn $func:ident() -> $kind:ident<$id:ty> = $name:literal;)*}) => {
+        impl Model for $model {
+            const RESOURCE_TYPE: &'static str = $resource_type;
+            const RELATIONSHIPS: &'static [&'static str] = &[$($name),*];
+        }
+
+        impl $model {
+            $(model!(@accessor $(#[$meta])* fn $func() -> $kind<$id> = $name);)*
+        }
+    };
+    (@accessor $(#[$meta:meta])* fn $func:ident() -> Option<$id:ty> = $name:literal) => {
+        $(#[$meta])*
+        pub fn $func(&self) -> Option<$id> {
+            self.relationship_id($name).map(<$id>::from)
+        }
+    };
+    (@accessor $(#[$meta:meta])* fn $func:ident() -> Vec<$id:ty> = $name:literal) => {
+        $(#[$meta])*
+        pub fn $func(&self) -> Vec<$id> {
+            self.relationship_ids($name).into_iter().map(<$id>::from).collect()
+        }
+    };
+}
+
 pub mod alert;
 pub use alert::*;
 pub mod datetime;
 pub use datetime::*;
 pub mod facility;
 pub use facility::*;
+pub mod id;
+pub use id::*;
+pub mod included;
+pub use included::*;
 pub mod line;
 pub use line::*;
 pub mod live_facility;
@@ -24,9 +55,71 @@ pub mod shape;
 pub use shape::*;
 pub mod shared;
 pub use shared::*;
+pub mod status;
+pub use status::*;
 pub mod stop;
 pub use stop::*;
 pub mod trip;
 pub use trip::*;
 pub mod vehicle;
 pub use vehicle::*;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::fmt::Debug;
+
+    use rstest::*;
+    use serde::{de::DeserializeOwned, Serialize};
+
+    /// Deserialize a JSON value and serialize it back, returning both results.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - the JSON value
+    fn round_trip<T: DeserializeOwned + Serialize>(input: &str) -> (T, String) {
+        let actual: T = serde_json::from_str(input).expect("failed to deserialize");
+        let serialized = serde_json::to_string(&actual).expect("failed to serialize");
+        (actual, serialized)
+    }
+
+    #[rstest]
+    #[case::lifecycle("\"ONGOING_UPCOMING\"", Lifecycle::OngoingUpcoming, "\"RETIRED\"", Lifecycle::Unknown("RETIRED".into()))]
+    #[case::effect("\"SHUTTLE\"", Effect::Shuttle, "\"TELEPORTATION\"", Effect::Unknown("TELEPORTATION".into()))]
+    #[case::cause("\"WEATHER\"", Cause::Weather, "\"SOLAR_FLARE\"", Cause::Unknown("SOLAR_FLARE".into()))]
+    #[case::activity("\"USING_WHEELCHAIR\"", Activity::UsingWheelchair, "\"USING_JETPACK\"", Activity::Unknown("USING_JETPACK".into()))]
+    #[case::facility_type("\"ELEVATOR\"", FacilityType::Elevator, "\"MOVING_WALKWAY\"", FacilityType::Unknown("MOVING_WALKWAY".into()))]
+    #[case::schedule_relationship(
+        "\"SKIPPED\"",
+        ScheduleRelationship::Skipped,
+        "\"DUPLICATED\"",
+        ScheduleRelationship::Unknown("DUPLICATED".into())
+    )]
+    #[case::occupancy_status(
+        "\"FEW_SEATS_AVAILABLE\"",
+        OccupancyStatus::FewSeatsAvailable,
+        "\"NOT_ACCEPTING_PASSENGERS\"",
+        OccupancyStatus::Unknown("NOT_ACCEPTING_PASSENGERS".into())
+    )]
+    #[case::current_status("\"IN_TRANSIT_TO\"", CurrentStatus::InTransitTo, "\"DETOURING\"", CurrentStatus::Unknown("DETOURING".into()))]
+    #[case::revenue_status("\"NON_REVENUE\"", RevenueStatus::NonRevenue, "\"DEADHEADING\"", RevenueStatus::Unknown("DEADHEADING".into()))]
+    fn test_enum_round_trip<T: DeserializeOwned + Serialize + PartialEq + Debug>(
+        #[case] known_input: &str,
+        #[case] known: T,
+        #[case] unknown_input: &str,
+        #[case] unknown: T,
+    ) {
+        // Arrange
+
+        // Act
+        let (actual_known, serialized_known) = round_trip::<T>(known_input);
+        let (actual_unknown, serialized_unknown) = round_trip::<T>(unknown_input);
+
+        // Assert
+        assert_eq!(actual_known, known);
+        assert_eq!(serialized_known, known_input);
+        assert_eq!(actual_unknown, unknown);
+        assert_eq!(serialized_unknown, unknown_input);
+    }
+}
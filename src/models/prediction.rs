@@ -30,10 +30,50 @@ pub struct PredictionAttributes {
     pub arrival_time: Option<DateTime<FixedOffset>>,
     /// How the predicted stop relates to the scheduled stops. [None] if the predicted stop was scheduled.
     pub schedule_relationship: Option<ScheduleRelationship>,
+    /// How the prediction was made, if reported.
+    #[serde(default)]
+    pub update_type: Option<UpdateType>,
+    /// Whether the predicted trip is in revenue service, if reported.
+    #[serde(default)]
+    pub revenue: Option<RevenueStatus>,
+}
+
+model!(
+    model = Prediction,
+    resource_type = "prediction",
+    relationships = {
+        /// ID of the route being predicted, if present in the relationships.
+        fn route_id() -> Option<RouteId> = "route";
+        /// ID of the stop being predicted, if present in the relationships.
+        fn stop_id() -> Option<StopId> = "stop";
+        /// ID of the trip being predicted, if present in the relationships.
+        fn trip_id() -> Option<TripId> = "trip";
+        /// ID of the vehicle making the trip, if present in the relationships.
+        fn vehicle_id() -> Option<VehicleId> = "vehicle";
+        /// ID of the schedule the prediction is for, if present in the relationships.
+        fn schedule_id() -> Option<&str> = "schedule";
+        /// IDs of the alerts affecting the prediction.
+        fn alert_ids() -> Vec<AlertId> = "alerts";
+    }
+);
+
+/// How a prediction was made.
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum UpdateType {
+    /// The vehicle is on the trip being predicted.
+    MidTrip,
+    /// The vehicle is at the terminal, waiting to start the trip.
+    AtTerminal,
+    /// The vehicle is finishing a trip in the opposite direction before starting the trip.
+    ReverseTrip,
+    /// A value added to the API after this version of the crate, kept as is.
+    #[serde(untagged)]
+    Unknown(String),
 }
 
 /// How a predicted stop relates to the scheduled stops.
-#[derive(Debug, PartialEq, Clone, Copy, Deserialize, Serialize)]
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum ScheduleRelationship {
     /// An extra trip that was added in addition to a running schedule, for example, to replace a broken vehicle or to respond to sudden passenger load.
@@ -46,4 +86,7 @@ pub enum ScheduleRelationship {
     Skipped,
     /// A trip that is running with no schedule associated to it.
     Unscheduled,
+    /// A value added to the API after this version of the crate, kept as is.
+    #[serde(untagged)]
+    Unknown(String),
 }
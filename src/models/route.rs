@@ -8,7 +8,7 @@ use super::*;
 pub type Routes = Vec<Route>;
 
 /// A path a vehicle travels during service.
-pub type Route = Resource<RouteAttributes>;
+pub type Route = Resource<RouteAttributes, RouteId>;
 
 /// Attributes for a route.
 #[derive(Deserialize, Serialize, Debug, PartialEq, Clone)]
@@ -39,3 +39,12 @@ pub struct RouteAttributes {
     /// Details about stops, schedule, and/or service.
     pub description: String,
 }
+
+model!(
+    model = Route,
+    resource_type = "route",
+    relationships = {
+        /// ID of the line the route belongs to, if present in the relationships.
+        fn line_id() -> Option<LineId> = "line";
+    }
+);
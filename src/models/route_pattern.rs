@@ -8,7 +8,7 @@ use super::*;
 pub type RoutePatterns = Vec<RoutePattern>;
 
 /// A different variation of service that may be run within a single route, including when and how often they are operated.
-pub type RoutePattern = Resource<RoutePatternAttributes>;
+pub type RoutePattern = Resource<RoutePatternAttributes, RoutePatternId>;
 
 /// Attributes for route pattern.
 #[derive(Deserialize, Serialize, Debug, PartialEq, Clone)]
@@ -24,8 +24,22 @@ pub struct RoutePatternAttributes {
     pub time_desc: Option<String>,
     /// Explains how common the route pattern is. For the MBTA, this is within the context of the entire route.
     pub typicality: RoutePatternTypicality,
+    /// Whether the route pattern is the canonical one for its route and direction, as shown on maps, if reported.
+    #[serde(default)]
+    pub canonical: Option<bool>,
 }
 
+model!(
+    model = RoutePattern,
+    resource_type = "route_pattern",
+    relationships = {
+        /// ID of the route the route pattern belongs to, if present in the relationships.
+        fn route_id() -> Option<RouteId> = "route";
+        /// ID of the representative trip of the route pattern, if present in the relationships.
+        fn representative_trip_id() -> Option<TripId> = "representative_trip";
+    }
+);
+
 /// How common a route pattern is. For the MBTA, this is within the context of the entire route.
 #[derive(Deserialize, Serialize, Debug, PartialEq, Clone, Copy)]
 #[serde(try_from = "u8")]
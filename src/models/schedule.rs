@@ -36,6 +36,21 @@ pub struct ScheduleAttributes {
     pub arrival_time: Option<DateTime<FixedOffset>>,
 }
 
+model!(
+    model = Schedule,
+    resource_type = "schedule",
+    relationships = {
+        /// ID of the route being scheduled, if present in the relationships.
+        fn route_id() -> Option<RouteId> = "route";
+        /// ID of the stop being scheduled, if present in the relationships.
+        fn stop_id() -> Option<StopId> = "stop";
+        /// ID of the trip being scheduled, if present in the relationships.
+        fn trip_id() -> Option<TripId> = "trip";
+        /// ID of the prediction for the schedule, if present in the relationships.
+        fn prediction_id() -> Option<&str> = "prediction";
+    }
+);
+
 /// Whether time points are exact or estimates.
 #[derive(Deserialize, Serialize, Debug, PartialEq, Clone, Copy)]
 #[serde(from = "bool")]
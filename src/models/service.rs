@@ -9,7 +9,9 @@ use super::*;
 pub type Services = Vec<Service>;
 
 /// A set of dates on which trips run.
-pub type Service = Resource<ServiceAttributes>;
+pub type Service = Resource<ServiceAttributes, ServiceId>;
+
+model!(model = Service, resource_type = "service", relationships = {});
 
 /// Attributes for service.
 #[derive(Debug, PartialEq, Clone, Deserialize, Serialize)]
@@ -8,7 +8,9 @@ use super::*;
 pub type Shapes = Vec<Shape>;
 
 /// A sequence of geographic points representing a path vehicles will travel on a trip.
-pub type Shape = Resource<ShapeAttributes>;
+pub type Shape = Resource<ShapeAttributes, ShapeId>;
+
+model!(model = Shape, resource_type = "shape", relationships = {});
 
 /// Attributes for a shape.
 #[derive(Debug, PartialEq, Clone, Deserialize, Serialize)]
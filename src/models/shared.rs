@@ -1,8 +1,12 @@
 //! Data models for shared/common data.
 
-use std::collections::HashMap;
+use std::{collections::HashMap, time::Duration};
 
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::IncludedResource;
+use crate::{find_header, HttpRequest, HttpResponse, ItemDecodeError, RateLimit, API_KEY_QUERY_PARAM, LAST_MODIFIED_HEADER, NOT_MODIFIED};
 
 /// MBTA V3 API response object.
 #[derive(Deserialize, Serialize, Debug, PartialEq, Clone)]
@@ -14,6 +18,73 @@ pub struct Response<D> {
     /// Links to different pages of the endpoint.
     #[serde(default)]
     pub links: Option<Links>,
+    /// Side-loaded resources requested through the `include` query parameter.
+    #[serde(default)]
+    pub included: Option<Vec<IncludedResource>>,
+    /// Metadata about the HTTP response the data came from, which isn't part of the JSON body.
+    #[serde(skip)]
+    pub meta: ResponseMeta,
+}
+
+/// Metadata about the HTTP response that an API response came from.
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct ResponseMeta {
+    /// HTTP status code of the response.
+    pub status: u16,
+    /// Response headers as pairings of names to values.
+    pub headers: Vec<(String, String)>,
+    /// URL of the request, without the query string.
+    pub url: String,
+    /// Query parameters of the request, leaving out an API key sent as a query parameter.
+    pub query: Vec<(String, String)>,
+    /// Time from sending the request to parsing the response, including any retries and rate-limit waits.
+    pub elapsed: Duration,
+    /// Rate-limit state reported with the response, if any.
+    pub rate_limit: Option<RateLimit>,
+    /// Whether the API answered a conditional request with `304 Not Modified`,
+    /// meaning the data is a cached copy of the previous response.
+    pub not_modified: bool,
+    /// Whether the response came from the client's response cache without sending a request.
+    pub cached: bool,
+    /// Items dropped from the data because they didn't match the data model, if lenient decoding is enabled.
+    pub item_errors: Vec<ItemDecodeError>,
+}
+
+impl ResponseMeta {
+    /// Create a [ResponseMeta] from a request and its response.
+    ///
+    /// # Arguments
+    ///
+    /// * `request` - the request
+    /// * `response` - the response
+    /// * `elapsed` - the time taken to get the response
+    pub(crate) fn new(request: &HttpRequest, response: &HttpResponse, elapsed: Duration) -> Self {
+        Self {
+            status: response.status,
+            headers: response.headers.clone(),
+            url: request.url.clone(),
+            query: request.query.iter().filter(|(name, _)| name != API_KEY_QUERY_PARAM).cloned().collect(),
+            elapsed,
+            rate_limit: RateLimit::from_headers(&response.headers),
+            not_modified: response.status == NOT_MODIFIED,
+            cached: false,
+            item_errors: vec![],
+        }
+    }
+
+    /// Value of a response header, matching the name case-insensitively.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - the header name
+    pub fn header(&self, name: &str) -> Option<&str> {
+        find_header(&self.headers, name)
+    }
+
+    /// Value of the `Last-Modified` header, with when the data last changed.
+    pub fn last_modified(&self) -> Option<&str> {
+        self.header(LAST_MODIFIED_HEADER)
+    }
 }
 
 /// Version of the JSON API.
@@ -29,6 +100,9 @@ pub struct Links {
     /// HTTP link to the first page of the endpoint.
     #[serde(default)]
     pub first: Option<String>,
+    /// HTTP link to the previous page of the endpoint.
+    #[serde(default)]
+    pub prev: Option<String>,
     /// HTTP link to the next page of the endpoint.
     #[serde(default)]
     pub next: Option<String>,
@@ -37,14 +111,70 @@ pub struct Links {
     pub last: Option<String>,
 }
 
+impl Links {
+    /// Parse the pagination metadata out of the page links.
+    pub fn page_info(&self) -> PageInfo {
+        let next_offset = self.next.as_deref().and_then(|link| page_param(link, "offset"));
+        let last_offset = self.last.as_deref().and_then(|link| page_param(link, "offset"));
+        let limit = [&self.first, &self.prev, &self.next, &self.last]
+            .into_iter()
+            .flatten()
+            .find_map(|link| page_param(link, "limit"));
+        let total_pages = match (last_offset, limit) {
+            (Some(last_offset), Some(limit)) if limit > 0 => Some(last_offset / limit + 1),
+            _ => None,
+        };
+        PageInfo {
+            next_offset,
+            last_offset,
+            limit,
+            total_pages,
+        }
+    }
+}
+
+/// Pagination metadata parsed from the [Links] of a paginated response.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub struct PageInfo {
+    /// The `page[offset]` of the next page, [None] if this is the last page.
+    pub next_offset: Option<u64>,
+    /// The `page[offset]` of the last page.
+    pub last_offset: Option<u64>,
+    /// The `page[limit]` of each page.
+    pub limit: Option<u64>,
+    /// The total number of pages.
+    pub total_pages: Option<u64>,
+}
+
+/// Parse a numeric `page[...]` query parameter out of a page link, accounting for percent-encoded brackets.
+///
+/// # Arguments
+///
+/// * `link` - the page link
+/// * `name` - the name inside the brackets, such as `offset` or `limit`
+fn page_param(link: &str, name: &str) -> Option<u64> {
+    let (_, query) = link.split_once('?')?;
+    query.split('&').find_map(|pair| {
+        let (key, value) = pair.split_once('=')?;
+        let key = key.replace("%5B", "[").replace("%5b", "[").replace("%5D", "]").replace("%5d", "]");
+        if key == format!("page[{}]", name) {
+            value.parse().ok()
+        } else {
+            None
+        }
+    })
+}
+
 /// Some MBTA resource, bundling common metadata with the actual model attributes.
+///
+/// The id is a strongly typed ID such as [StopId](crate::StopId) for the resources this crate models, and a plain [String] otherwise.
 #[derive(Deserialize, Serialize, Debug, PartialEq, Clone)]
-pub struct Resource<Attribute> {
+pub struct Resource<Attribute, Id = String> {
     /// The JSON API resource type.
     #[serde(rename = "type")]
     pub resource_type: String,
     /// The JSON API resource id.
-    pub id: String,
+    pub id: Id,
     /// Related endpoint links. *This field could use some more documentation.*
     #[serde(default)]
     pub links: Option<HashMap<String, String>>,
@@ -55,21 +185,151 @@ pub struct Resource<Attribute> {
     pub relationships: Option<HashMap<String, Relationships>>,
 }
 
+impl<Attribute, Id> Resource<Attribute, Id> {
+    /// Returns the atomic data of a to-one relationship to another data model, if the relationship is present.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - the name of the relationship, such as `"route"` or `"stop"`
+    pub fn relationship(&self, name: &str) -> Option<&RelationshipAtom> {
+        self.relationships.as_ref()?.get(name)?.one()
+    }
+
+    /// Returns the ID of the related data model for a to-one relationship, if the relationship is present.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - the name of the relationship, such as `"route"` or `"stop"`
+    pub fn relationship_id(&self, name: &str) -> Option<&str> {
+        self.relationship(name).map(|atom| atom.id.as_str())
+    }
+
+    /// Returns the atomic data of all related data models for a relationship, whether it is to-one or to-many.
+    /// The slice is empty if the relationship is not present.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - the name of the relationship, such as `"child_stops"` or `"facilities"`
+    pub fn relationship_atoms(&self, name: &str) -> &[RelationshipAtom] {
+        match self.relationships.as_ref().and_then(|r| r.get(name)) {
+            Some(relationship) => relationship.atoms(),
+            None => &[],
+        }
+    }
+
+    /// Returns the IDs of all related data models for a relationship, whether it is to-one or to-many.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - the name of the relationship, such as `"child_stops"` or `"facilities"`
+    pub fn relationship_ids(&self, name: &str) -> Vec<&str> {
+        self.relationship_atoms(name).iter().map(|atom| atom.id.as_str()).collect()
+    }
+}
+
+/// A data model for a resource type of the V3 API, along with the relationships it has accessors for.
+pub trait Model {
+    /// The JSON API resource type, such as `"stop"`.
+    const RESOURCE_TYPE: &'static str;
+
+    /// Names of the relationships with accessors, such as `"parent_station"`.
+    const RELATIONSHIPS: &'static [&'static str];
+}
+
 /// A model's relationships to other data models.
 #[derive(Deserialize, Serialize, Debug, PartialEq, Clone)]
 pub struct Relationships {
-    /// Another model that is related to this data model.
-    pub data: Option<RelationshipAtom>,
+    /// Linkage to the other model(s) that are related to this data model.
+    #[serde(default)]
+    pub data: Option<RelationshipData>,
+    /// Related endpoint links for the relationship.
+    #[serde(default)]
+    pub links: Option<HashMap<String, String>>,
+    /// Non-standard meta information about the relationship.
+    #[serde(default)]
+    pub meta: Option<HashMap<String, Value>>,
+}
+
+impl Relationships {
+    /// Returns the atomic data of the related model if this is a to-one relationship.
+    pub fn one(&self) -> Option<&RelationshipAtom> {
+        self.data.as_ref()?.one()
+    }
+
+    /// Returns the atomic data of all related models, whether this is a to-one or to-many relationship.
+    pub fn atoms(&self) -> &[RelationshipAtom] {
+        match &self.data {
+            Some(data) => data.atoms(),
+            None => &[],
+        }
+    }
+
+    /// Returns whether this is a to-many relationship.
+    pub fn is_to_many(&self) -> bool {
+        matches!(self.data, Some(RelationshipData::ToMany(_)))
+    }
+}
+
+/// Resource linkage of a relationship: either a single related model or many of them.
+#[derive(Deserialize, Serialize, Debug, PartialEq, Clone)]
+#[serde(untagged)]
+pub enum RelationshipData {
+    /// A to-one relationship.
+    ToOne(RelationshipAtom),
+    /// A to-many relationship.
+    ToMany(Vec<RelationshipAtom>),
+}
+
+impl RelationshipData {
+    /// Returns the atomic data of the related model if this is a to-one relationship.
+    pub fn one(&self) -> Option<&RelationshipAtom> {
+        match self {
+            Self::ToOne(atom) => Some(atom),
+            Self::ToMany(_) => None,
+        }
+    }
+
+    /// Returns the atomic data of all related models, whether this is a to-one or to-many relationship.
+    pub fn atoms(&self) -> &[RelationshipAtom] {
+        match self {
+            Self::ToOne(atom) => std::slice::from_ref(atom),
+            Self::ToMany(atoms) => atoms,
+        }
+    }
 }
 
 /// Atomic data for relationships between data models.
+///
+/// The id is a strongly typed ID such as [VehicleId](crate::VehicleId) when the type of the related model is known ahead of time,
+/// as for the removals of a stream, and a plain [String] for the relationships of a [Resource].
 #[derive(Deserialize, Serialize, Debug, PartialEq, Clone)]
-pub struct RelationshipAtom {
+pub struct RelationshipAtom<Id = String> {
     /// The type of the related model.
     #[serde(rename = "type")]
     pub relationship_type: String,
     /// The ID of the related model.
-    pub id: String,
+    pub id: Id,
+}
+
+impl<Id> RelationshipAtom<Id> {
+    /// Convert the ID into another type, such as turning a plain [String] into a [RouteId](crate::RouteId)
+    /// once `relationship_type` is known.
+    pub fn into_typed<T: From<Id>>(self) -> RelationshipAtom<T> {
+        RelationshipAtom {
+            relationship_type: self.relationship_type,
+            id: self.id.into(),
+        }
+    }
+}
+
+/// A data model along with the type of its JSON API resource id.
+pub trait Identified {
+    /// Type of the resource id, such as [VehicleId](crate::VehicleId).
+    type Id;
+}
+
+impl<Attribute, Id> Identified for Resource<Attribute, Id> {
+    type Id = Id;
 }
 
 /// The type of transportation something can support.
@@ -152,12 +412,133 @@ impl From<WheelchairAccessible> for u8 {
     }
 }
 
+/// Whether a trip or vehicle carries passengers.
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum RevenueStatus {
+    /// In service, carrying passengers.
+    Revenue,
+    /// Not carrying passengers, such as when heading to or from a yard.
+    NonRevenue,
+    /// A value added to the API after this version of the crate, kept as is.
+    #[serde(untagged)]
+    Unknown(String),
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     use rstest::*;
 
+    #[rstest]
+    #[case::no_included("{\"data\": [], \"jsonapi\": {\"version\": \"1.0\"}}", None)]
+    #[case::some_included(
+        "{\"data\": [], \"jsonapi\": {\"version\": \"1.0\"}, \"included\": [{\"type\": \"shape\", \"id\": \"foo\", \"attributes\": {\"polyline\": \"abc\"}}]}",
+        Some(vec![("shape".into(), "foo".into())])
+    )]
+    fn test_response_deserialize_included(#[case] input: &str, #[case] expected: Option<Vec<(String, String)>>) {
+        // Arrange
+
+        // Act
+        let response: Response<Vec<Resource<Value>>> = serde_json::from_str(input).expect("failed to deserialize");
+        let actual = response
+            .included
+            .map(|included| included.iter().map(|i| (i.resource_type().to_string(), i.id().to_string())).collect());
+
+        // Assert
+        assert_eq!(actual, expected);
+    }
+
+    #[rstest]
+    #[case::to_one("{\"data\": {\"type\": \"stop\", \"id\": \"a\"}}", false, Some("a"), vec!["a"])]
+    #[case::to_many("{\"data\": [{\"type\": \"stop\", \"id\": \"a\"}, {\"type\": \"stop\", \"id\": \"b\"}]}", true, None, vec!["a", "b"])]
+    #[case::empty_to_many("{\"data\": []}", true, None, vec![])]
+    #[case::null_data("{\"data\": null}", false, None, vec![])]
+    #[case::links_only("{\"links\": {\"related\": \"/stops/a\"}, \"meta\": {\"count\": 1}}", false, None, vec![])]
+    fn test_relationships_deserialize(
+        #[case] input: &str,
+        #[case] expected_to_many: bool,
+        #[case] expected_one: Option<&str>,
+        #[case] expected_atoms: Vec<&str>,
+    ) {
+        // Arrange
+
+        // Act
+        let actual: Relationships = serde_json::from_str(input).expect("failed to deserialize");
+
+        // Assert
+        assert_eq!(actual.is_to_many(), expected_to_many);
+        assert_eq!(actual.one().map(|atom| atom.id.as_str()), expected_one);
+        assert_eq!(actual.atoms().iter().map(|atom| atom.id.as_str()).collect::<Vec<&str>>(), expected_atoms);
+    }
+
+    #[rstest]
+    fn test_resource_relationship_accessors() {
+        // Arrange
+        let input = "{\"type\": \"stop\", \"id\": \"place-pktrm\", \"attributes\": null, \"relationships\": {\
+            \"parent_station\": {\"data\": {\"type\": \"stop\", \"id\": \"p\"}}, \
+            \"child_stops\": {\"data\": [{\"type\": \"stop\", \"id\": \"c1\"}, {\"type\": \"stop\", \"id\": \"c2\"}]}}}";
+
+        // Act
+        let actual: Resource<Value> = serde_json::from_str(input).expect("failed to deserialize");
+
+        // Assert
+        assert_eq!(actual.relationship_id("parent_station"), Some("p"));
+        assert_eq!(actual.relationship_id("child_stops"), None);
+        assert_eq!(actual.relationship_ids("parent_station"), vec!["p"]);
+        assert_eq!(actual.relationship_ids("child_stops"), vec!["c1", "c2"]);
+        assert!(actual.relationship_ids("facilities").is_empty());
+    }
+
+    #[rstest]
+    fn test_relationship_atom_into_typed() {
+        // Arrange
+        let input = "{\"type\": \"route\", \"id\": \"Red\"}";
+        let atom: RelationshipAtom = serde_json::from_str(input).expect("failed to deserialize");
+
+        // Act
+        let actual: RelationshipAtom<crate::RouteId> = atom.into_typed();
+
+        // Assert
+        assert_eq!(actual.id, crate::RouteId::new("Red"));
+        assert_eq!(actual.relationship_type, "route");
+    }
+
+    #[rstest]
+    #[case::middle_page(
+        Links {
+            first: Some("https://api-v3.mbta.com/stops?page%5Blimit%5D=10&page%5Boffset%5D=0".into()),
+            prev: Some("https://api-v3.mbta.com/stops?page%5Blimit%5D=10&page%5Boffset%5D=0".into()),
+            next: Some("https://api-v3.mbta.com/stops?page%5Blimit%5D=10&page%5Boffset%5D=20".into()),
+            last: Some("https://api-v3.mbta.com/stops?page%5Blimit%5D=10&page%5Boffset%5D=90".into()),
+        },
+        PageInfo { next_offset: Some(20), last_offset: Some(90), limit: Some(10), total_pages: Some(10) },
+    )]
+    #[case::last_page(
+        Links {
+            first: Some("https://api-v3.mbta.com/stops?page[limit]=10&page[offset]=0".into()),
+            prev: None,
+            next: None,
+            last: Some("https://api-v3.mbta.com/stops?page[limit]=10&page[offset]=0".into()),
+        },
+        PageInfo { next_offset: None, last_offset: Some(0), limit: Some(10), total_pages: Some(1) },
+    )]
+    #[case::no_links(Links { first: None, prev: None, next: None, last: None }, PageInfo::default())]
+    #[case::malformed_links(
+        Links { first: Some("foobar".into()), prev: None, next: Some("foo?page[offset]=bar".into()), last: None },
+        PageInfo::default(),
+    )]
+    fn test_links_page_info(#[case] input: Links, #[case] expected: PageInfo) {
+        // Arrange
+
+        // Act
+        let actual = input.page_info();
+
+        // Assert
+        assert_eq!(actual, expected);
+    }
+
     #[rstest]
     #[case::zero(0, Ok(RouteType::LightRail))]
     #[case::one(1, Ok(RouteType::HeavyRail))]
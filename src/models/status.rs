@@ -0,0 +1,31 @@
+//! Data models for the status of the MBTA V3 API.
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use super::*;
+
+/// Status of the API, such as which GTFS feed it is serving.
+pub type Status = Resource<StatusAttributes>;
+
+model!(model = Status, resource_type = "status", relationships = {});
+
+/// Attributes for the API status.
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize)]
+pub struct StatusAttributes {
+    /// The GTFS feed the API is serving.
+    pub feed: Feed,
+}
+
+/// A version of the GTFS feed.
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize)]
+pub struct Feed {
+    /// Version of the feed.
+    pub version: String,
+    /// Earliest date the feed has service for.
+    #[serde(with = "naive_mbta_date_format")]
+    pub start_date: NaiveDate,
+    /// Latest date the feed has service for.
+    #[serde(with = "naive_mbta_date_format")]
+    pub end_date: NaiveDate,
+}
@@ -8,7 +8,7 @@ use super::*;
 pub type Stops = Vec<Stop>;
 
 /// A physical location where transit can pick-up or drop-off passengers.
-pub type Stop = Resource<StopAttributes>;
+pub type Stop = Resource<StopAttributes, StopId>;
 
 /// Attributes for a stop.
 #[derive(Debug, PartialEq, Clone, Deserialize, Serialize)]
@@ -41,6 +41,19 @@ pub struct StopAttributes {
     pub location_type: LocationType,
 }
 
+model!(
+    model = Stop,
+    resource_type = "stop",
+    relationships = {
+        /// ID of the parent station of the stop, if present in the relationships.
+        fn parent_station_id() -> Option<StopId> = "parent_station";
+        /// IDs of the child stops of the stop.
+        fn child_stop_ids() -> Vec<StopId> = "child_stops";
+        /// IDs of the facilities at the stop.
+        fn facility_ids() -> Vec<FacilityId> = "facilities";
+    }
+);
+
 /// The type of stop.
 #[derive(Debug, PartialEq, Clone, Copy, Deserialize, Serialize)]
 #[serde(try_from = "u8")]
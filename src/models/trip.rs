@@ -8,7 +8,7 @@ use super::*;
 pub type Trips = Vec<Trip>;
 
 /// The journey of a particular vehicle through a given set of stops.
-pub type Trip = Resource<TripAttributes>;
+pub type Trip = Resource<TripAttributes, TripId>;
 
 /// Attributes for a trip.
 #[derive(Debug, PartialEq, Clone, Deserialize, Serialize)]
@@ -26,8 +26,26 @@ pub struct TripAttributes {
     pub block_id: String,
     /// Indicator of whether or not bikes are allowed on this trip.
     pub bikes_allowed: BikesAllowed,
+    /// Whether the trip is in revenue service, if reported.
+    #[serde(default)]
+    pub revenue: Option<RevenueStatus>,
 }
 
+model!(
+    model = Trip,
+    resource_type = "trip",
+    relationships = {
+        /// ID of the route the trip belongs to, if present in the relationships.
+        fn route_id() -> Option<RouteId> = "route";
+        /// ID of the service the trip runs on, if present in the relationships.
+        fn service_id() -> Option<ServiceId> = "service";
+        /// ID of the shape the trip travels along, if present in the relationships.
+        fn shape_id() -> Option<ShapeId> = "shape";
+        /// ID of the route pattern the trip follows, if present in the relationships.
+        fn route_pattern_id() -> Option<RoutePatternId> = "route_pattern";
+    }
+);
+
 /// Whether or not a bike is allowed.
 #[derive(Debug, PartialEq, Clone, Copy, Deserialize, Serialize)]
 #[serde(try_from = "u8")]
@@ -9,7 +9,7 @@ use super::*;
 pub type Vehicles = Vec<Vehicle>;
 
 /// Current state of a vehicle on a trip.
-pub type Vehicle = Resource<VehicleAttributes>;
+pub type Vehicle = Resource<VehicleAttributes, VehicleId>;
 
 /// Attributes for a vehicle.
 #[derive(Debug, PartialEq, Clone, Deserialize, Serialize)]
@@ -36,10 +36,43 @@ pub struct VehicleAttributes {
     /// Bearing, in degrees, clockwise from True North, i.e., 0 is North and 90 is East.
     /// This can be the compass bearing, or the direction towards the next stop or intermediate location.
     pub bearing: u64,
+    /// Individual cars of the vehicle, front to back, for vehicles that report them.
+    #[serde(default)]
+    pub carriages: Vec<Carriage>,
+    /// Whether the vehicle is in revenue service, if reported.
+    #[serde(default)]
+    pub revenue: Option<RevenueStatus>,
 }
 
+/// A single car of a vehicle.
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize)]
+pub struct Carriage {
+    /// Car number, such as the one on the signage on the car.
+    #[serde(default)]
+    pub label: Option<String>,
+    /// The degree of passenger occupancy for the car.
+    #[serde(default)]
+    pub occupancy_status: Option<OccupancyStatus>,
+    /// Percentage of the car's capacity that is occupied.
+    #[serde(default)]
+    pub occupancy_percentage: Option<u64>,
+}
+
+model!(
+    model = Vehicle,
+    resource_type = "vehicle",
+    relationships = {
+        /// ID of the route the vehicle is on, if present in the relationships.
+        fn route_id() -> Option<RouteId> = "route";
+        /// ID of the stop the vehicle is at or heading to, if present in the relationships.
+        fn stop_id() -> Option<StopId> = "stop";
+        /// ID of the trip the vehicle is making, if present in the relationships.
+        fn trip_id() -> Option<TripId> = "trip";
+    }
+);
+
 /// Degree of passenger occupancy.
-#[derive(Debug, PartialEq, Clone, Copy, Deserialize, Serialize)]
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum OccupancyStatus {
     /// Not crowded: the vehicle has a large percentage of seats available.
@@ -48,10 +81,13 @@ pub enum OccupancyStatus {
     FewSeatsAvailable,
     /// Crowded: the vehicle is considered full by most measures, but may still be allowing passengers to board.
     Full,
+    /// A value added to the API after this version of the crate, kept as is.
+    #[serde(untagged)]
+    Unknown(String),
 }
 
 /// Status relative to stops.
-#[derive(Debug, PartialEq, Clone, Copy, Deserialize, Serialize)]
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum CurrentStatus {
     /// Just about to arrive at a stop.
@@ -60,4 +96,7 @@ pub enum CurrentStatus {
     StoppedAt,
     /// Departed the previous stop and is in transit.
     InTransitTo,
+    /// A value added to the API after this version of the crate, kept as is.
+    #[serde(untagged)]
+    Unknown(String),
 }
@@ -0,0 +1,133 @@
+//! Lazy pagination over the plural endpoints of the V3 API.
+
+use std::{collections::VecDeque, marker::PhantomData};
+
+use serde::de::DeserializeOwned;
+
+use super::*;
+
+/// Iterator over the pages of a plural endpoint, following the `next` link of each page.
+///
+/// Each call to [Iterator::next] issues at most one request; iteration stops after the last page
+/// or after the first error, which is yielded before stopping.
+/// Pages are only split up by the API if a `page[limit]` query parameter is given.
+#[derive(Debug)]
+pub struct Pages<'a, D> {
+    /// Client that makes the requests.
+    client: &'a Client,
+    /// Endpoint being paginated.
+    endpoint: String,
+    /// Query parameters sent with every request, without `page[offset]`.
+    query_params: Vec<(String, String)>,
+    /// Offset of the page to request next, [None] once iteration is finished.
+    next_offset: Option<u64>,
+    /// Whether the initial request should send an explicit offset.
+    send_offset: bool,
+    /// Pagination metadata from the most recently fetched page.
+    page_info: Option<PageInfo>,
+    /// Data type of each page.
+    data: PhantomData<D>,
+}
+
+impl<'a, D> Pages<'a, D> {
+    /// Create a new [Pages] iterator. Presumes that all query parameters given are valid.
+    ///
+    /// # Arguments
+    ///
+    /// * `client` - the client that makes the requests
+    /// * `endpoint` - the endpoint to paginate
+    /// * `query_params` - a slice of pairings of query parameter names to values
+    pub(crate) fn new<K: AsRef<str>, V: AsRef<str>>(client: &'a Client, endpoint: &str, query_params: &[(K, V)]) -> Self {
+        let mut start_offset = None;
+        let query_params = query_params
+            .iter()
+            .filter_map(|(k, v)| {
+                if k.as_ref() == "page[offset]" {
+                    start_offset = v.as_ref().parse().ok();
+                    None
+                } else {
+                    Some((k.as_ref().to_string(), v.as_ref().to_string()))
+                }
+            })
+            .collect();
+        Self {
+            client,
+            endpoint: endpoint.into(),
+            query_params,
+            next_offset: Some(start_offset.unwrap_or(0)),
+            send_offset: start_offset.is_some(),
+            page_info: None,
+            data: PhantomData,
+        }
+    }
+
+    /// Pagination metadata (offsets, limit, and total pages) from the most recently fetched page.
+    /// [None] if no page has been fetched yet or the endpoint isn't split into pages.
+    pub fn page_info(&self) -> Option<PageInfo> {
+        self.page_info
+    }
+
+    /// Flatten the pages into an iterator over individual items.
+    pub fn items(self) -> Items<'a, D>
+    where
+        D: IntoIterator,
+    {
+        Items {
+            pages: self,
+            buffer: VecDeque::new(),
+        }
+    }
+}
+
+impl<'a, D: DeserializeOwned + Clone + Send + Sync + 'static> Iterator for Pages<'a, D> {
+    type Item = Result<Response<D>, ClientError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let offset = self.next_offset.take()?;
+        let mut query_params = self.query_params.clone();
+        if self.send_offset || offset > 0 {
+            query_params.push(("page[offset]".into(), offset.to_string()));
+        }
+        let response = self.client.get::<D, String, String>(&self.endpoint, &query_params);
+        if let Ok(response) = &response {
+            let page_info = response.links.as_ref().map(Links::page_info);
+            self.next_offset = page_info.and_then(|p| p.next_offset).filter(|next| *next > offset);
+            self.page_info = page_info;
+        }
+        Some(response)
+    }
+}
+
+/// Iterator over the individual items of a paginated plural endpoint, lazily fetching pages as needed.
+///
+/// Iteration stops after the first error, which is yielded before stopping.
+#[derive(Debug)]
+pub struct Items<'a, D: IntoIterator> {
+    /// Underlying page iterator.
+    pages: Pages<'a, D>,
+    /// Items from the current page that haven't been yielded yet.
+    buffer: VecDeque<D::Item>,
+}
+
+impl<'a, D: IntoIterator> Items<'a, D> {
+    /// Pagination metadata (offsets, limit, and total pages) from the most recently fetched page.
+    pub fn page_info(&self) -> Option<PageInfo> {
+        self.pages.page_info()
+    }
+}
+
+impl<'a, D: DeserializeOwned + Clone + Send + Sync + IntoIterator + 'static> Iterator for Items<'a, D> {
+    type Item = Result<D::Item, ClientError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(item) = self.buffer.pop_front() {
+                return Some(Ok(item));
+            }
+            match self.pages.next()? {
+                Ok(page) => self.buffer.extend(page.data),
+                Err(e) => return Some(Err(e)),
+            }
+        }
+    }
+}
@@ -0,0 +1,1331 @@
+//! Typed, validated query parameter builders for the plural endpoints of the V3 API.
+//!
+//! Each builder produces the same `(name, value)` pairs that the plural endpoints on [Client] accept,
+//! but catches invalid values (such as a direction ID of 2 or an out-of-range latitude) before any request is sent.
+//!
+//! ```
+//! # use std::env;
+//! use mbta_rs::*;
+//!
+//! # let client = match env::var("MBTA_TOKEN") {
+//! #     Ok(token) => Client::with_key(token),
+//! #     Err(_) => Client::without_key()
+//! # };
+//! let query = StopsQuery::new()
+//!     .route(["Red", "Orange"])
+//!     .location_type([LocationType::Station])
+//!     .sort(StopsSort::Name, SortOrder::Ascending)
+//!     .page_limit(10)
+//!     .build()
+//!     .expect("invalid query");
+//! let stops_response = client.stops(&query);
+//! ```
+
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, FixedOffset, NaiveDate};
+use serde::Serialize;
+use serde_json::Value;
+
+use super::*;
+
+/// Direction of a sort.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum SortOrder {
+    /// Smallest values first.
+    Ascending,
+    /// Largest values first.
+    Descending,
+}
+
+/// Macro for declaring the fields an endpoint can be sorted by.
+macro_rules! sort_fields {
+    (name=$name:ident, endpoint=$endpoint:literal, fields=[$($variant:ident => $field:literal),+ $(,)?]) => {
+        #[doc = concat!("Fields that the `", $endpoint, "` endpoint can be sorted by.")]
+        #[derive(Debug, PartialEq, Eq, Clone, Copy)]
+        pub enum $name {
+            $(
+                #[doc = concat!("Sort by `", $field, "`.")]
+                $variant,
+            )+
+        }
+
+        impl $name {
+            /// The name of the field as expected by the API.
+            pub fn as_str(&self) -> &'static str {
+                match self {
+                    $(Self::$variant => $field,)+
+                }
+            }
+        }
+    };
+}
+
+/// Macro for declaring a query builder along with the query parameters every plural endpoint shares.
+macro_rules! query_builder {
+    (name=$name:ident, sort=$sort:ident, endpoint=$endpoint:literal) => {
+        #[doc = concat!("Typed, validated query parameters for the `", $endpoint, "` endpoint.")]
+        #[derive(Debug, PartialEq, Clone, Default)]
+        pub struct $name {
+            /// Query parameters set so far.
+            params: QueryParams,
+        }
+
+        impl $name {
+            #[doc = concat!("Create a new [", stringify!($name), "] without any query parameters.")]
+            pub fn new() -> Self {
+                Self::default()
+            }
+
+            /// Offset of the first item to return.
+            pub fn page_offset(mut self, offset: u64) -> Self {
+                self.params.set("page[offset]", offset.to_string());
+                self
+            }
+
+            /// Maximum number of items to return; must be greater than 0.
+            pub fn page_limit(mut self, limit: u64) -> Self {
+                if limit == 0 {
+                    self.params.reject("page[limit]", "0", "must be greater than 0");
+                }
+                self.params.set("page[limit]", limit.to_string());
+                self
+            }
+
+            /// Sort by a field. Calling this multiple times sorts by each field in the order given.
+            ///
+            /// # Arguments
+            ///
+            /// * `field` - the field to sort by
+            /// * `order` - the direction of the sort
+            pub fn sort(mut self, field: $sort, order: SortOrder) -> Self {
+                self.params.sort(field.as_str(), order);
+                self
+            }
+
+            /// Side-load related resources into the response's `included` member. Multiple values are joined with commas.
+            pub fn include<I: IntoIterator<Item = S>, S: AsRef<str>>(mut self, relationships: I) -> Self {
+                self.params.list("include", relationships);
+                self
+            }
+
+            /// Validate and return the query parameters as pairings of names to values.
+            pub fn build(&self) -> Result<Vec<(String, String)>, ClientError> {
+                self.params.build()
+            }
+        }
+    };
+}
+
+/// Latest hour of a time filter; hours past 23 are times after midnight on the service date.
+pub const MAX_SERVICE_HOUR: u8 = 47;
+
+/// Query parameters shared by all builders, along with the first invalid value found.
+#[derive(Debug, PartialEq, Clone, Default)]
+struct QueryParams {
+    /// Query parameter names to values.
+    params: BTreeMap<String, String>,
+    /// Name, value, and reason of the first invalid query parameter.
+    invalid: Option<(String, String, String)>,
+}
+
+impl QueryParams {
+    /// Set a query parameter, replacing any previous value.
+    fn set(&mut self, name: &str, value: String) {
+        self.params.insert(name.into(), value);
+    }
+
+    /// Record an invalid query parameter value, keeping only the first one recorded.
+    fn reject(&mut self, name: &str, value: &str, reason: &str) {
+        if self.invalid.is_none() {
+            self.invalid = Some((name.into(), value.into(), reason.into()));
+        }
+    }
+
+    /// Set a multi-valued query parameter, joining the values with commas.
+    fn set_list<I: IntoIterator<Item = String>>(&mut self, name: &str, values: I) {
+        let values: Vec<String> = values.into_iter().collect();
+        let joined = values.join(",");
+        if values.is_empty() {
+            self.reject(name, &joined, "must have at least one value");
+        } else if values.iter().any(|v| v.is_empty() || v.contains(',')) {
+            self.reject(name, &joined, "values must be non-empty and can't contain commas");
+        }
+        self.set(name, joined);
+    }
+
+    /// Set a multi-valued query parameter from strings.
+    fn list<I: IntoIterator<Item = S>, S: AsRef<str>>(&mut self, name: &str, values: I) {
+        self.set_list(name, values.into_iter().map(|v| v.as_ref().to_string()));
+    }
+
+    /// Set a multi-valued query parameter from enums, using their serialized form.
+    /// Values that fail to serialize are rejected rather than sent.
+    fn enum_list<I: IntoIterator<Item = T>, T: Serialize>(&mut self, name: &str, values: I) {
+        let mut serialized_values = vec![];
+        for value in values {
+            match serialized(&value) {
+                Ok(value) => serialized_values.push(value),
+                Err(e) => self.reject(name, "", &format!("failed to serialize: {}", e)),
+            }
+        }
+        self.set_list(name, serialized_values);
+    }
+
+    /// Set the direction ID filter.
+    fn direction_id(&mut self, direction_id: u8) {
+        if direction_id > 1 {
+            self.reject("filter[direction_id]", &direction_id.to_string(), "must be 0 or 1");
+        }
+        self.set("filter[direction_id]", direction_id.to_string());
+    }
+
+    /// Set a date filter.
+    fn date(&mut self, name: &str, date: NaiveDate) {
+        self.set(name, date.format(DATE_FORMAT).to_string());
+    }
+
+    /// Set the latitude and longitude filters.
+    fn location(&mut self, latitude: f64, longitude: f64) {
+        if !(-90.0..=90.0).contains(&latitude) {
+            self.reject("filter[latitude]", &latitude.to_string(), "must be between -90 and 90");
+        }
+        if !(-180.0..=180.0).contains(&longitude) {
+            self.reject("filter[longitude]", &longitude.to_string(), "must be between -180 and 180");
+        }
+        self.set("filter[latitude]", latitude.to_string());
+        self.set("filter[longitude]", longitude.to_string());
+    }
+
+    /// Set the radius filter.
+    fn radius(&mut self, radius: f64) {
+        if !(radius.is_finite() && radius > 0.0) {
+            self.reject("filter[radius]", &radius.to_string(), "must be greater than 0");
+        }
+        self.set("filter[radius]", radius.to_string());
+    }
+
+    /// Set the severity filter.
+    fn severity<I: IntoIterator<Item = u8>>(&mut self, values: I) {
+        let values: Vec<u8> = values.into_iter().collect();
+        if let Some(value) = values.iter().find(|v| **v > 10) {
+            self.reject("filter[severity]", &value.to_string(), "must be between 0 and 10");
+        }
+        self.set_list("filter[severity]", values.into_iter().map(|v| v.to_string()));
+    }
+
+    /// Set a time filter in `HH:MM` format, with hours up to [MAX_SERVICE_HOUR] for times after midnight on the service date.
+    fn time(&mut self, name: &str, time: &str) {
+        let valid = match time.split_once(':') {
+            Some((hours, minutes)) => {
+                hours.len() == 2
+                    && minutes.len() == 2
+                    && hours.chars().all(|c| c.is_ascii_digit())
+                    && hours.parse::<u8>().map(|h| h <= MAX_SERVICE_HOUR).unwrap_or(false)
+                    && minutes.parse::<u8>().map(|m| m < 60).unwrap_or(false)
+            }
+            None => false,
+        };
+        if !valid {
+            self.reject(name, time, "must be in HH:MM format with hours up to 47");
+        }
+        self.set(name, time.into());
+    }
+
+    /// Add a field to the sort parameter.
+    fn sort(&mut self, field: &str, order: SortOrder) {
+        let field = match order {
+            SortOrder::Ascending => field.to_string(),
+            SortOrder::Descending => format!("-{}", field),
+        };
+        match self.params.get_mut("sort") {
+            Some(sort) => {
+                sort.push(',');
+                sort.push_str(&field);
+            }
+            None => self.set("sort", field),
+        }
+    }
+
+    /// Validate and return the query parameters.
+    fn build(&self) -> Result<Vec<(String, String)>, ClientError> {
+        if let Some((name, value, reason)) = &self.invalid {
+            return Err(ClientError::InvalidQueryValue {
+                name: name.clone(),
+                value: value.clone(),
+                reason: reason.clone(),
+            });
+        }
+        if let Some(radius) = self.params.get("filter[radius]") {
+            if !self.params.contains_key("filter[latitude]") {
+                return Err(ClientError::InvalidQueryValue {
+                    name: "filter[radius]".into(),
+                    value: radius.clone(),
+                    reason: "requires a location".into(),
+                });
+            }
+        }
+        Ok(self.params.iter().map(|(k, v)| (k.clone(), v.clone())).collect())
+    }
+}
+
+/// The serialized form of a value as a query parameter value, such as `1` for [RouteType::HeavyRail].
+fn serialized<T: Serialize>(value: &T) -> Result<String, serde_json::Error> {
+    match serde_json::to_value(value)? {
+        Value::String(s) => Ok(s),
+        other => Ok(other.to_string()),
+    }
+}
+
+sort_fields!(
+    name = AlertsSort,
+    endpoint = "alerts",
+    fields = [
+        ActivePeriod => "active_period",
+        Banner => "banner",
+        Cause => "cause",
+        CreatedAt => "created_at",
+        Description => "description",
+        Effect => "effect",
+        Header => "header",
+        InformedEntity => "informed_entity",
+        Lifecycle => "lifecycle",
+        ServiceEffect => "service_effect",
+        Severity => "severity",
+        ShortHeader => "short_header",
+        Timeframe => "timeframe",
+        UpdatedAt => "updated_at",
+        Url => "url"
+    ]
+);
+query_builder!(name = AlertsQuery, sort = AlertsSort, endpoint = "alerts");
+
+impl AlertsQuery {
+    /// Filter by affected activities. Multiple values are joined with commas.
+    pub fn activity<I: IntoIterator<Item = Activity>>(mut self, values: I) -> Self {
+        self.params.enum_list("filter[activity]", values);
+        self
+    }
+
+    /// Filter by affected route types. Multiple values are joined with commas.
+    pub fn route_type<I: IntoIterator<Item = RouteType>>(mut self, values: I) -> Self {
+        self.params.enum_list("filter[route_type]", values);
+        self
+    }
+
+    /// Filter by direction of travel: 0 or 1.
+    pub fn direction_id(mut self, direction_id: u8) -> Self {
+        self.params.direction_id(direction_id);
+        self
+    }
+
+    /// Filter by affected route IDs. Multiple values are joined with commas.
+    pub fn route<I: IntoIterator<Item = S>, S: Into<RouteId>>(mut self, values: I) -> Self {
+        self.params.list("filter[route]", values.into_iter().map(Into::<RouteId>::into));
+        self
+    }
+
+    /// Filter by affected stop IDs. Multiple values are joined with commas.
+    pub fn stop<I: IntoIterator<Item = S>, S: Into<StopId>>(mut self, values: I) -> Self {
+        self.params.list("filter[stop]", values.into_iter().map(Into::<StopId>::into));
+        self
+    }
+
+    /// Filter by affected trip IDs. Multiple values are joined with commas.
+    pub fn trip<I: IntoIterator<Item = S>, S: Into<TripId>>(mut self, values: I) -> Self {
+        self.params.list("filter[trip]", values.into_iter().map(Into::<TripId>::into));
+        self
+    }
+
+    /// Filter by affected facility IDs. Multiple values are joined with commas.
+    pub fn facility<I: IntoIterator<Item = S>, S: Into<FacilityId>>(mut self, values: I) -> Self {
+        self.params.list("filter[facility]", values.into_iter().map(Into::<FacilityId>::into));
+        self
+    }
+
+    /// Filter by alert IDs. Multiple values are joined with commas.
+    pub fn id<I: IntoIterator<Item = S>, S: Into<AlertId>>(mut self, values: I) -> Self {
+        self.params.list("filter[id]", values.into_iter().map(Into::<AlertId>::into));
+        self
+    }
+
+    /// Filter by whether an alert is meant to be displayed prominently as a banner.
+    pub fn banner(mut self, banner: bool) -> Self {
+        self.params.set("filter[banner]", banner.to_string());
+        self
+    }
+
+    /// Filter by alerts that are active at a given datetime.
+    pub fn datetime(mut self, datetime: DateTime<FixedOffset>) -> Self {
+        self.params.set("filter[datetime]", datetime.format(DATETIME_FORMAT).to_string());
+        self
+    }
+
+    /// Filter by alerts that are active now.
+    pub fn datetime_now(mut self) -> Self {
+        self.params.set("filter[datetime]", "NOW".into());
+        self
+    }
+
+    /// Filter by lifecycles. Multiple values are joined with commas.
+    pub fn lifecycle<I: IntoIterator<Item = Lifecycle>>(mut self, values: I) -> Self {
+        self.params.enum_list("filter[lifecycle]", values);
+        self
+    }
+
+    /// Filter by severity, from 0 (least severe) to 10 (most severe). Multiple values are joined with commas.
+    pub fn severity<I: IntoIterator<Item = u8>>(mut self, values: I) -> Self {
+        self.params.severity(values);
+        self
+    }
+}
+
+sort_fields!(
+    name = FacilitiesSort,
+    endpoint = "facilities",
+    fields = [
+        Latitude => "latitude",
+        LongName => "long_name",
+        Longitude => "longitude",
+        Properties => "properties",
+        ShortName => "short_name",
+        Type => "type"
+    ]
+);
+query_builder!(name = FacilitiesQuery, sort = FacilitiesSort, endpoint = "facilities");
+
+impl FacilitiesQuery {
+    /// Filter by stop IDs. Multiple values are joined with commas.
+    pub fn stop<I: IntoIterator<Item = S>, S: Into<StopId>>(mut self, values: I) -> Self {
+        self.params.list("filter[stop]", values.into_iter().map(Into::<StopId>::into));
+        self
+    }
+
+    /// Filter by facility types. Multiple values are joined with commas.
+    pub fn facility_type<I: IntoIterator<Item = FacilityType>>(mut self, values: I) -> Self {
+        self.params.enum_list("filter[type]", values);
+        self
+    }
+}
+
+sort_fields!(
+    name = LinesSort,
+    endpoint = "lines",
+    fields = [
+        Color => "color",
+        LongName => "long_name",
+        ShortName => "short_name",
+        SortOrder => "sort_order",
+        TextColor => "text_color"
+    ]
+);
+query_builder!(name = LinesQuery, sort = LinesSort, endpoint = "lines");
+
+impl LinesQuery {
+    /// Filter by line IDs. Multiple values are joined with commas.
+    pub fn id<I: IntoIterator<Item = S>, S: Into<LineId>>(mut self, values: I) -> Self {
+        self.params.list("filter[id]", values.into_iter().map(Into::<LineId>::into));
+        self
+    }
+}
+
+sort_fields!(
+    name = LiveFacilitiesSort,
+    endpoint = "live_facilities",
+    fields = [
+        Properties => "properties",
+        UpdatedAt => "updated_at"
+    ]
+);
+query_builder!(name = LiveFacilitiesQuery, sort = LiveFacilitiesSort, endpoint = "live_facilities");
+
+impl LiveFacilitiesQuery {
+    /// Filter by facility IDs. Multiple values are joined with commas.
+    pub fn id<I: IntoIterator<Item = S>, S: Into<FacilityId>>(mut self, values: I) -> Self {
+        self.params.list("filter[id]", values.into_iter().map(Into::<FacilityId>::into));
+        self
+    }
+}
+
+sort_fields!(
+    name = PredictionsSort,
+    endpoint = "predictions",
+    fields = [
+        ArrivalTime => "arrival_time",
+        DepartureTime => "departure_time",
+        DirectionId => "direction_id",
+        ScheduleRelationship => "schedule_relationship",
+        Status => "status",
+        StopSequence => "stop_sequence"
+    ]
+);
+query_builder!(name = PredictionsQuery, sort = PredictionsSort, endpoint = "predictions");
+
+impl PredictionsQuery {
+    /// Filter by distance from a location; see [Self::radius].
+    ///
+    /// # Arguments
+    ///
+    /// * `latitude` - degrees North, in the WGS-84 coordinate system
+    /// * `longitude` - degrees East, in the WGS-84 coordinate system
+    pub fn location(mut self, latitude: f64, longitude: f64) -> Self {
+        self.params.location(latitude, longitude);
+        self
+    }
+
+    /// The radius around [Self::location] to filter by, in degrees (roughly 69 miles per degree).
+    pub fn radius(mut self, radius: f64) -> Self {
+        self.params.radius(radius);
+        self
+    }
+
+    /// Filter by direction of travel: 0 or 1.
+    pub fn direction_id(mut self, direction_id: u8) -> Self {
+        self.params.direction_id(direction_id);
+        self
+    }
+
+    /// Filter by route types. Multiple values are joined with commas.
+    pub fn route_type<I: IntoIterator<Item = RouteType>>(mut self, values: I) -> Self {
+        self.params.enum_list("filter[route_type]", values);
+        self
+    }
+
+    /// Filter by stop IDs. Multiple values are joined with commas.
+    pub fn stop<I: IntoIterator<Item = S>, S: Into<StopId>>(mut self, values: I) -> Self {
+        self.params.list("filter[stop]", values.into_iter().map(Into::<StopId>::into));
+        self
+    }
+
+    /// Filter by route IDs. Multiple values are joined with commas.
+    pub fn route<I: IntoIterator<Item = S>, S: Into<RouteId>>(mut self, values: I) -> Self {
+        self.params.list("filter[route]", values.into_iter().map(Into::<RouteId>::into));
+        self
+    }
+
+    /// Filter by trip IDs. Multiple values are joined with commas.
+    pub fn trip<I: IntoIterator<Item = S>, S: Into<TripId>>(mut self, values: I) -> Self {
+        self.params.list("filter[trip]", values.into_iter().map(Into::<TripId>::into));
+        self
+    }
+
+    /// Filter by route pattern IDs. Multiple values are joined with commas.
+    pub fn route_pattern<I: IntoIterator<Item = S>, S: Into<RoutePatternId>>(mut self, values: I) -> Self {
+        self.params.list("filter[route_pattern]", values.into_iter().map(Into::<RoutePatternId>::into));
+        self
+    }
+
+    /// Filter by whether the vehicle of the prediction's trip carries passengers.
+    pub fn revenue(mut self, revenue: RevenueStatus) -> Self {
+        self.params.enum_list("filter[revenue]", [revenue]);
+        self
+    }
+}
+
+sort_fields!(
+    name = RoutesSort,
+    endpoint = "routes",
+    fields = [
+        Color => "color",
+        Description => "description",
+        DirectionDestinations => "direction_destinations",
+        DirectionNames => "direction_names",
+        FareClass => "fare_class",
+        LongName => "long_name",
+        ShortName => "short_name",
+        SortOrder => "sort_order",
+        TextColor => "text_color",
+        Type => "type"
+    ]
+);
+query_builder!(name = RoutesQuery, sort = RoutesSort, endpoint = "routes");
+
+impl RoutesQuery {
+    /// Filter by stop IDs. Multiple values are joined with commas.
+    pub fn stop<I: IntoIterator<Item = S>, S: Into<StopId>>(mut self, values: I) -> Self {
+        self.params.list("filter[stop]", values.into_iter().map(Into::<StopId>::into));
+        self
+    }
+
+    /// Filter by route types. Multiple values are joined with commas.
+    pub fn route_type<I: IntoIterator<Item = RouteType>>(mut self, values: I) -> Self {
+        self.params.enum_list("filter[type]", values);
+        self
+    }
+
+    /// Filter by direction of travel: 0 or 1.
+    pub fn direction_id(mut self, direction_id: u8) -> Self {
+        self.params.direction_id(direction_id);
+        self
+    }
+
+    /// Filter by service date.
+    pub fn date(mut self, date: NaiveDate) -> Self {
+        self.params.date("filter[date]", date);
+        self
+    }
+
+    /// Filter by route IDs. Multiple values are joined with commas.
+    pub fn id<I: IntoIterator<Item = S>, S: Into<RouteId>>(mut self, values: I) -> Self {
+        self.params.list("filter[id]", values.into_iter().map(Into::<RouteId>::into));
+        self
+    }
+}
+
+sort_fields!(
+    name = RoutePatternsSort,
+    endpoint = "route_patterns",
+    fields = [
+        DirectionId => "direction_id",
+        Name => "name",
+        SortOrder => "sort_order",
+        TimeDesc => "time_desc",
+        Typicality => "typicality"
+    ]
+);
+query_builder!(name = RoutePatternsQuery, sort = RoutePatternsSort, endpoint = "route_patterns");
+
+impl RoutePatternsQuery {
+    /// Filter by route pattern IDs. Multiple values are joined with commas.
+    pub fn id<I: IntoIterator<Item = S>, S: Into<RoutePatternId>>(mut self, values: I) -> Self {
+        self.params.list("filter[id]", values.into_iter().map(Into::<RoutePatternId>::into));
+        self
+    }
+
+    /// Filter by route IDs. Multiple values are joined with commas.
+    pub fn route<I: IntoIterator<Item = S>, S: Into<RouteId>>(mut self, values: I) -> Self {
+        self.params.list("filter[route]", values.into_iter().map(Into::<RouteId>::into));
+        self
+    }
+
+    /// Filter by direction of travel: 0 or 1.
+    pub fn direction_id(mut self, direction_id: u8) -> Self {
+        self.params.direction_id(direction_id);
+        self
+    }
+
+    /// Filter by stop IDs. Multiple values are joined with commas.
+    pub fn stop<I: IntoIterator<Item = S>, S: Into<StopId>>(mut self, values: I) -> Self {
+        self.params.list("filter[stop]", values.into_iter().map(Into::<StopId>::into));
+        self
+    }
+
+    /// Filter by whether the route pattern is the canonical pattern of its route and direction.
+    pub fn canonical(mut self, canonical: bool) -> Self {
+        self.params.set("filter[canonical]", canonical.to_string());
+        self
+    }
+
+    /// Filter by the service date the route patterns are in effect.
+    pub fn date(mut self, date: NaiveDate) -> Self {
+        self.params.date("filter[date]", date);
+        self
+    }
+}
+
+sort_fields!(
+    name = SchedulesSort,
+    endpoint = "schedules",
+    fields = [
+        ArrivalTime => "arrival_time",
+        DepartureTime => "departure_time",
+        DirectionId => "direction_id",
+        DropOffType => "drop_off_type",
+        PickupType => "pickup_type",
+        StopHeadsign => "stop_headsign",
+        StopSequence => "stop_sequence",
+        Timepoint => "timepoint"
+    ]
+);
+query_builder!(name = SchedulesQuery, sort = SchedulesSort, endpoint = "schedules");
+
+impl SchedulesQuery {
+    /// Filter by service date.
+    pub fn date(mut self, date: NaiveDate) -> Self {
+        self.params.date("filter[date]", date);
+        self
+    }
+
+    /// Filter by direction of travel: 0 or 1.
+    pub fn direction_id(mut self, direction_id: u8) -> Self {
+        self.params.direction_id(direction_id);
+        self
+    }
+
+    /// Filter by route types. Multiple values are joined with commas.
+    pub fn route_type<I: IntoIterator<Item = RouteType>>(mut self, values: I) -> Self {
+        self.params.enum_list("filter[route_type]", values);
+        self
+    }
+
+    /// Filter by the earliest time of a schedule, in `HH:MM` format.
+    /// Use hours past 24, up to [MAX_SERVICE_HOUR], for times after midnight on the service date, such as `25:30`.
+    pub fn min_time<S: AsRef<str>>(mut self, time: S) -> Self {
+        self.params.time("filter[min_time]", time.as_ref());
+        self
+    }
+
+    /// Filter by the latest time of a schedule, in `HH:MM` format.
+    /// Use hours past 24, up to [MAX_SERVICE_HOUR], for times after midnight on the service date, such as `25:30`.
+    pub fn max_time<S: AsRef<str>>(mut self, time: S) -> Self {
+        self.params.time("filter[max_time]", time.as_ref());
+        self
+    }
+
+    /// Filter by route IDs. Multiple values are joined with commas.
+    pub fn route<I: IntoIterator<Item = S>, S: Into<RouteId>>(mut self, values: I) -> Self {
+        self.params.list("filter[route]", values.into_iter().map(Into::<RouteId>::into));
+        self
+    }
+
+    /// Filter by stop IDs. Multiple values are joined with commas.
+    pub fn stop<I: IntoIterator<Item = S>, S: Into<StopId>>(mut self, values: I) -> Self {
+        self.params.list("filter[stop]", values.into_iter().map(Into::<StopId>::into));
+        self
+    }
+
+    /// Filter by trip IDs. Multiple values are joined with commas.
+    pub fn trip<I: IntoIterator<Item = S>, S: Into<TripId>>(mut self, values: I) -> Self {
+        self.params.list("filter[trip]", values.into_iter().map(Into::<TripId>::into));
+        self
+    }
+
+    /// Filter by stop sequence along a trip. Multiple values are joined with commas.
+    pub fn stop_sequence<I: IntoIterator<Item = u64>>(mut self, values: I) -> Self {
+        self.params.set_list("filter[stop_sequence]", values.into_iter().map(|v| v.to_string()));
+        self
+    }
+}
+
+sort_fields!(
+    name = ServicesSort,
+    endpoint = "services",
+    fields = [
+        AddedDates => "added_dates",
+        AddedDatesNotes => "added_dates_notes",
+        Description => "description",
+        EndDate => "end_date",
+        RatingDescription => "rating_description",
+        RatingEndDate => "rating_end_date",
+        RatingStartDate => "rating_start_date",
+        RemovedDates => "removed_dates",
+        RemovedDatesNotes => "removed_dates_notes",
+        ScheduleName => "schedule_name",
+        ScheduleType => "schedule_type",
+        ScheduleTypicality => "schedule_typicality",
+        StartDate => "start_date",
+        ValidDays => "valid_days"
+    ]
+);
+query_builder!(name = ServicesQuery, sort = ServicesSort, endpoint = "services");
+
+impl ServicesQuery {
+    /// Filter by service IDs. Multiple values are joined with commas.
+    pub fn id<I: IntoIterator<Item = S>, S: Into<ServiceId>>(mut self, values: I) -> Self {
+        self.params.list("filter[id]", values.into_iter().map(Into::<ServiceId>::into));
+        self
+    }
+
+    /// Filter by route IDs. Multiple values are joined with commas.
+    pub fn route<I: IntoIterator<Item = S>, S: Into<RouteId>>(mut self, values: I) -> Self {
+        self.params.list("filter[route]", values.into_iter().map(Into::<RouteId>::into));
+        self
+    }
+}
+
+sort_fields!(
+    name = ShapesSort,
+    endpoint = "shapes",
+    fields = [
+        Polyline => "polyline"
+    ]
+);
+query_builder!(name = ShapesQuery, sort = ShapesSort, endpoint = "shapes");
+
+impl ShapesQuery {
+    /// Filter by route IDs. Multiple values are joined with commas.
+    pub fn route<I: IntoIterator<Item = S>, S: Into<RouteId>>(mut self, values: I) -> Self {
+        self.params.list("filter[route]", values.into_iter().map(Into::<RouteId>::into));
+        self
+    }
+}
+
+sort_fields!(
+    name = StopsSort,
+    endpoint = "stops",
+    fields = [
+        Address => "address",
+        AtStreet => "at_street",
+        Description => "description",
+        Latitude => "latitude",
+        LocationType => "location_type",
+        Longitude => "longitude",
+        Municipality => "municipality",
+        Name => "name",
+        OnStreet => "on_street",
+        PlatformCode => "platform_code",
+        PlatformName => "platform_name",
+        VehicleType => "vehicle_type",
+        WheelchairBoarding => "wheelchair_boarding",
+        Distance => "distance"
+    ]
+);
+query_builder!(name = StopsQuery, sort = StopsSort, endpoint = "stops");
+
+impl StopsQuery {
+    /// Filter by service date.
+    pub fn date(mut self, date: NaiveDate) -> Self {
+        self.params.date("filter[date]", date);
+        self
+    }
+
+    /// Filter by direction of travel: 0 or 1.
+    pub fn direction_id(mut self, direction_id: u8) -> Self {
+        self.params.direction_id(direction_id);
+        self
+    }
+
+    /// Filter by distance from a location; see [Self::radius].
+    ///
+    /// # Arguments
+    ///
+    /// * `latitude` - degrees North, in the WGS-84 coordinate system
+    /// * `longitude` - degrees East, in the WGS-84 coordinate system
+    pub fn location(mut self, latitude: f64, longitude: f64) -> Self {
+        self.params.location(latitude, longitude);
+        self
+    }
+
+    /// The radius around [Self::location] to filter by, in degrees (roughly 69 miles per degree).
+    pub fn radius(mut self, radius: f64) -> Self {
+        self.params.radius(radius);
+        self
+    }
+
+    /// Filter by stop IDs. Multiple values are joined with commas.
+    pub fn id<I: IntoIterator<Item = S>, S: Into<StopId>>(mut self, values: I) -> Self {
+        self.params.list("filter[id]", values.into_iter().map(Into::<StopId>::into));
+        self
+    }
+
+    /// Filter by route types. Multiple values are joined with commas.
+    pub fn route_type<I: IntoIterator<Item = RouteType>>(mut self, values: I) -> Self {
+        self.params.enum_list("filter[route_type]", values);
+        self
+    }
+
+    /// Filter by route IDs. Multiple values are joined with commas.
+    pub fn route<I: IntoIterator<Item = S>, S: Into<RouteId>>(mut self, values: I) -> Self {
+        self.params.list("filter[route]", values.into_iter().map(Into::<RouteId>::into));
+        self
+    }
+
+    /// Filter by service IDs. Multiple values are joined with commas.
+    pub fn service<I: IntoIterator<Item = S>, S: Into<ServiceId>>(mut self, values: I) -> Self {
+        self.params.list("filter[service]", values.into_iter().map(Into::<ServiceId>::into));
+        self
+    }
+
+    /// Filter by location types. Multiple values are joined with commas.
+    pub fn location_type<I: IntoIterator<Item = LocationType>>(mut self, values: I) -> Self {
+        self.params.enum_list("filter[location_type]", values);
+        self
+    }
+
+    /// Filter by parent station IDs. Multiple values are joined with commas.
+    pub fn parent_station<I: IntoIterator<Item = S>, S: Into<StopId>>(mut self, values: I) -> Self {
+        self.params.list("filter[parent_station]", values.into_iter().map(Into::<StopId>::into));
+        self
+    }
+}
+
+sort_fields!(
+    name = TripsSort,
+    endpoint = "trips",
+    fields = [
+        BikesAllowed => "bikes_allowed",
+        BlockId => "block_id",
+        DirectionId => "direction_id",
+        Headsign => "headsign",
+        Name => "name",
+        WheelchairAccessible => "wheelchair_accessible"
+    ]
+);
+query_builder!(name = TripsQuery, sort = TripsSort, endpoint = "trips");
+
+impl TripsQuery {
+    /// Filter by service date.
+    pub fn date(mut self, date: NaiveDate) -> Self {
+        self.params.date("filter[date]", date);
+        self
+    }
+
+    /// Filter by direction of travel: 0 or 1.
+    pub fn direction_id(mut self, direction_id: u8) -> Self {
+        self.params.direction_id(direction_id);
+        self
+    }
+
+    /// Filter by route IDs. Multiple values are joined with commas.
+    pub fn route<I: IntoIterator<Item = S>, S: Into<RouteId>>(mut self, values: I) -> Self {
+        self.params.list("filter[route]", values.into_iter().map(Into::<RouteId>::into));
+        self
+    }
+
+    /// Filter by route pattern IDs. Multiple values are joined with commas.
+    pub fn route_pattern<I: IntoIterator<Item = S>, S: Into<RoutePatternId>>(mut self, values: I) -> Self {
+        self.params.list("filter[route_pattern]", values.into_iter().map(Into::<RoutePatternId>::into));
+        self
+    }
+
+    /// Filter by trip IDs. Multiple values are joined with commas.
+    pub fn id<I: IntoIterator<Item = S>, S: Into<TripId>>(mut self, values: I) -> Self {
+        self.params.list("filter[id]", values.into_iter().map(Into::<TripId>::into));
+        self
+    }
+
+    /// Filter by trip names. Multiple values are joined with commas.
+    pub fn name<I: IntoIterator<Item = S>, S: AsRef<str>>(mut self, values: I) -> Self {
+        self.params.list("filter[name]", values);
+        self
+    }
+
+    /// Filter by whether the trip carries passengers.
+    pub fn revenue(mut self, revenue: RevenueStatus) -> Self {
+        self.params.enum_list("filter[revenue]", [revenue]);
+        self
+    }
+}
+
+sort_fields!(
+    name = VehiclesSort,
+    endpoint = "vehicles",
+    fields = [
+        Bearing => "bearing",
+        CurrentStatus => "current_status",
+        CurrentStopSequence => "current_stop_sequence",
+        DirectionId => "direction_id",
+        Label => "label",
+        Latitude => "latitude",
+        Longitude => "longitude",
+        Speed => "speed",
+        UpdatedAt => "updated_at"
+    ]
+);
+query_builder!(name = VehiclesQuery, sort = VehiclesSort, endpoint = "vehicles");
+
+impl VehiclesQuery {
+    /// Filter by vehicle IDs. Multiple values are joined with commas.
+    pub fn id<I: IntoIterator<Item = S>, S: Into<VehicleId>>(mut self, values: I) -> Self {
+        self.params.list("filter[id]", values.into_iter().map(Into::<VehicleId>::into));
+        self
+    }
+
+    /// Filter by trip IDs. Multiple values are joined with commas.
+    pub fn trip<I: IntoIterator<Item = S>, S: Into<TripId>>(mut self, values: I) -> Self {
+        self.params.list("filter[trip]", values.into_iter().map(Into::<TripId>::into));
+        self
+    }
+
+    /// Filter by vehicle labels. Multiple values are joined with commas.
+    pub fn label<I: IntoIterator<Item = S>, S: AsRef<str>>(mut self, values: I) -> Self {
+        self.params.list("filter[label]", values);
+        self
+    }
+
+    /// Filter by route IDs. Multiple values are joined with commas.
+    pub fn route<I: IntoIterator<Item = S>, S: Into<RouteId>>(mut self, values: I) -> Self {
+        self.params.list("filter[route]", values.into_iter().map(Into::<RouteId>::into));
+        self
+    }
+
+    /// Filter by direction of travel: 0 or 1.
+    pub fn direction_id(mut self, direction_id: u8) -> Self {
+        self.params.direction_id(direction_id);
+        self
+    }
+
+    /// Filter by route types. Multiple values are joined with commas.
+    pub fn route_type<I: IntoIterator<Item = RouteType>>(mut self, values: I) -> Self {
+        self.params.enum_list("filter[route_type]", values);
+        self
+    }
+
+    /// Filter by whether the vehicle carries passengers.
+    pub fn revenue(mut self, revenue: RevenueStatus) -> Self {
+        self.params.enum_list("filter[revenue]", [revenue]);
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use rstest::*;
+
+    fn pairs(input: &[(&str, &str)]) -> Vec<(String, String)> {
+        input.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+    }
+
+    #[rstest]
+    fn test_stops_query_build() {
+        // Arrange
+        let query = StopsQuery::new()
+            .route(["Red", "Orange"])
+            .location_type([LocationType::Station, LocationType::Stop])
+            .route_type([RouteType::HeavyRail])
+            .location(42.35, -71.06)
+            .radius(0.01)
+            .direction_id(1)
+            .date(NaiveDate::from_ymd_opt(2022, 5, 8).expect("invalid date"))
+            .sort(StopsSort::Name, SortOrder::Ascending)
+            .sort(StopsSort::Distance, SortOrder::Descending)
+            .include(["parent_station"])
+            .page_limit(5);
+        let expected = pairs(&[
+            ("filter[date]", "2022-05-08"),
+            ("filter[direction_id]", "1"),
+            ("filter[latitude]", "42.35"),
+            ("filter[location_type]", "1,0"),
+            ("filter[longitude]", "-71.06"),
+            ("filter[radius]", "0.01"),
+            ("filter[route]", "Red,Orange"),
+            ("filter[route_type]", "1"),
+            ("include", "parent_station"),
+            ("page[limit]", "5"),
+            ("sort", "name,-distance"),
+        ]);
+
+        // Act
+        let actual = query.build().expect("failed to build query");
+
+        // Assert
+        assert_eq!(actual, expected);
+    }
+
+    #[rstest]
+    fn test_vehicles_query_typed_ids() {
+        // Arrange
+        let query = VehiclesQuery::new().id([VehicleId::new("y1234")]).route([RouteId::new("Red")]).trip(["52417812"]);
+        let expected = pairs(&[("filter[id]", "y1234"), ("filter[route]", "Red"), ("filter[trip]", "52417812")]);
+
+        // Act
+        let actual = query.build().expect("failed to build query");
+
+        // Assert
+        assert_eq!(actual, expected);
+    }
+
+    #[rstest]
+    fn test_alerts_query_build() {
+        // Arrange
+        let query = AlertsQuery::new()
+            .activity([Activity::Board, Activity::UsingWheelchair])
+            .lifecycle([Lifecycle::New])
+            .severity([3, 10])
+            .banner(false)
+            .datetime_now();
+        let expected = pairs(&[
+            ("filter[activity]", "BOARD,USING_WHEELCHAIR"),
+            ("filter[banner]", "false"),
+            ("filter[datetime]", "NOW"),
+            ("filter[lifecycle]", "NEW"),
+            ("filter[severity]", "3,10"),
+        ]);
+
+        // Act
+        let actual = query.build().expect("failed to build query");
+
+        // Assert
+        assert_eq!(actual, expected);
+    }
+
+    #[rstest]
+    #[case::direction_id(TripsQuery::new().direction_id(2), "filter[direction_id]", "2")]
+    #[case::page_limit(TripsQuery::new().page_limit(0), "page[limit]", "0")]
+    #[case::empty_list(TripsQuery::new().route(Vec::<String>::new()), "filter[route]", "")]
+    #[case::comma_in_value(TripsQuery::new().route(["Red,Blue"]), "filter[route]", "Red,Blue")]
+    fn test_trips_query_invalid(#[case] query: TripsQuery, #[case] expected_name: &str, #[case] expected_value: &str) {
+        // Arrange
+
+        // Act
+        let error = query.build().expect_err("query did not fail");
+
+        // Assert
+        if let ClientError::InvalidQueryValue { name, value, .. } = error {
+            assert_eq!(name, expected_name);
+            assert_eq!(value, expected_value);
+        } else {
+            panic!("wrong error type");
+        }
+    }
+
+    #[rstest]
+    #[case::latitude(PredictionsQuery::new().location(91.0, 0.0), "filter[latitude]")]
+    #[case::longitude(PredictionsQuery::new().location(0.0, -181.0), "filter[longitude]")]
+    #[case::radius(PredictionsQuery::new().location(0.0, 0.0).radius(-1.0), "filter[radius]")]
+    #[case::radius_without_location(PredictionsQuery::new().radius(1.0), "filter[radius]")]
+    fn test_predictions_query_invalid(#[case] query: PredictionsQuery, #[case] expected_name: &str) {
+        // Arrange
+
+        // Act
+        let error = query.build().expect_err("query did not fail");
+
+        // Assert
+        if let ClientError::InvalidQueryValue { name, .. } = error {
+            assert_eq!(name, expected_name);
+        } else {
+            panic!("wrong error type");
+        }
+    }
+
+    #[rstest]
+    #[case::valid("08:30", true)]
+    #[case::after_midnight("25:30", true)]
+    #[case::bad_minutes("08:60", false)]
+    #[case::no_colon("0830", false)]
+    #[case::single_digit_hour("8:30", false)]
+    #[case::last_service_hour("47:59", true)]
+    #[case::past_service_day("48:00", false)]
+    #[case::out_of_range_hour("99:00", false)]
+    fn test_schedules_query_time(#[case] input: &str, #[case] expected_valid: bool) {
+        // Arrange
+        let query = SchedulesQuery::new().min_time(input).max_time("26:00");
+
+        // Act
+        let actual = query.build();
+
+        // Assert
+        assert_eq!(actual.is_ok(), expected_valid);
+    }
+
+    /// Value that always fails to serialize.
+    struct Unserializable;
+
+    impl Serialize for Unserializable {
+        fn serialize<S: serde::Serializer>(&self, _serializer: S) -> Result<S::Ok, S::Error> {
+            Err(serde::ser::Error::custom("unserializable"))
+        }
+    }
+
+    #[rstest]
+    fn test_enum_list_serialization_error() {
+        // Arrange
+        let mut params = QueryParams::default();
+
+        // Act
+        params.enum_list("filter[activity]", [Unserializable]);
+        let actual = params.build();
+
+        // Assert
+        if let Err(ClientError::InvalidQueryValue { name, value, reason }) = actual {
+            assert_eq!(name, "filter[activity]");
+            assert_eq!(value, "");
+            assert_eq!(reason, "failed to serialize: unserializable");
+        } else {
+            panic!("wrong error type");
+        }
+    }
+
+    #[rstest]
+    #[case::alerts(
+        AlertsQuery::new()
+            .activity([Activity::Board])
+            .route_type([RouteType::HeavyRail])
+            .direction_id(0)
+            .route(["Red"])
+            .stop(["place-sstat"])
+            .trip(["52417812"])
+            .facility(["955"])
+            .id(["1"])
+            .banner(true)
+            .datetime_now()
+            .lifecycle([Lifecycle::New])
+            .severity([3])
+            .sort(AlertsSort::ActivePeriod, SortOrder::Ascending)
+            .include(["stops"])
+            .page_offset(0)
+            .page_limit(1)
+            .build(),
+        Alerts::ALLOWED_QUERY_PARAMS
+    )]
+    #[case::facilities(
+        FacilitiesQuery::new()
+            .stop(["place-sstat"])
+            .facility_type([FacilityType::Elevator])
+            .sort(FacilitiesSort::Latitude, SortOrder::Ascending)
+            .include(["stop"])
+            .page_offset(0)
+            .page_limit(1)
+            .build(),
+        Facilities::ALLOWED_QUERY_PARAMS
+    )]
+    #[case::lines(
+        LinesQuery::new()
+            .id(["line-Red"])
+            .sort(LinesSort::Color, SortOrder::Ascending)
+            .include(["routes"])
+            .page_offset(0)
+            .page_limit(1)
+            .build(),
+        Lines::ALLOWED_QUERY_PARAMS
+    )]
+    #[case::live_facilities(
+        LiveFacilitiesQuery::new()
+            .id(["955"])
+            .sort(LiveFacilitiesSort::Properties, SortOrder::Ascending)
+            .include(["facility"])
+            .page_offset(0)
+            .page_limit(1)
+            .build(),
+        LiveFacilities::ALLOWED_QUERY_PARAMS
+    )]
+    #[case::predictions(
+        PredictionsQuery::new()
+            .location(42.35, -71.06)
+            .radius(0.01)
+            .direction_id(0)
+            .route_type([RouteType::HeavyRail])
+            .stop(["place-sstat"])
+            .route(["Red"])
+            .trip(["52417812"])
+            .route_pattern(["Red-1-0"])
+            .revenue(RevenueStatus::Revenue)
+            .sort(PredictionsSort::ArrivalTime, SortOrder::Ascending)
+            .include(["trip"])
+            .page_offset(0)
+            .page_limit(1)
+            .build(),
+        Predictions::ALLOWED_QUERY_PARAMS
+    )]
+    #[case::routes(
+        RoutesQuery::new()
+            .stop(["place-sstat"])
+            .route_type([RouteType::HeavyRail])
+            .direction_id(0)
+            .date(NaiveDate::from_ymd_opt(2022, 5, 8).expect("invalid date"))
+            .id(["Red"])
+            .sort(RoutesSort::Color, SortOrder::Ascending)
+            .include(["line"])
+            .page_offset(0)
+            .page_limit(1)
+            .build(),
+        Routes::ALLOWED_QUERY_PARAMS
+    )]
+    #[case::route_patterns(
+        RoutePatternsQuery::new()
+            .id(["Red-1-0"])
+            .route(["Red"])
+            .direction_id(0)
+            .stop(["place-sstat"])
+            .canonical(true)
+            .date(NaiveDate::from_ymd_opt(2022, 5, 8).expect("invalid date"))
+            .sort(RoutePatternsSort::DirectionId, SortOrder::Ascending)
+            .include(["route"])
+            .page_offset(0)
+            .page_limit(1)
+            .build(),
+        RoutePatterns::ALLOWED_QUERY_PARAMS
+    )]
+    #[case::schedules(
+        SchedulesQuery::new()
+            .date(NaiveDate::from_ymd_opt(2022, 5, 8).expect("invalid date"))
+            .direction_id(0)
+            .route_type([RouteType::HeavyRail])
+            .min_time("08:00")
+            .max_time("25:30")
+            .route(["Red"])
+            .stop(["place-sstat"])
+            .trip(["52417812"])
+            .stop_sequence([1])
+            .sort(SchedulesSort::ArrivalTime, SortOrder::Ascending)
+            .include(["trip"])
+            .page_offset(0)
+            .page_limit(1)
+            .build(),
+        Schedules::ALLOWED_QUERY_PARAMS
+    )]
+    #[case::services(
+        ServicesQuery::new()
+            .id(["canonical"])
+            .route(["Red"])
+            .sort(ServicesSort::AddedDates, SortOrder::Ascending)
+            .include(["route"])
+            .page_offset(0)
+            .page_limit(1)
+            .build(),
+        Services::ALLOWED_QUERY_PARAMS
+    )]
+    #[case::shapes(
+        ShapesQuery::new()
+            .route(["Red"])
+            .sort(ShapesSort::Polyline, SortOrder::Ascending)
+            .include(["stops"])
+            .page_offset(0)
+            .page_limit(1)
+            .build(),
+        Shapes::ALLOWED_QUERY_PARAMS
+    )]
+    #[case::stops(
+        StopsQuery::new()
+            .date(NaiveDate::from_ymd_opt(2022, 5, 8).expect("invalid date"))
+            .direction_id(0)
+            .location(42.35, -71.06)
+            .radius(0.01)
+            .id(["place-sstat"])
+            .route_type([RouteType::HeavyRail])
+            .route(["Red"])
+            .service(["canonical"])
+            .location_type([LocationType::Station])
+            .parent_station(["place-sstat"])
+            .sort(StopsSort::Address, SortOrder::Ascending)
+            .include(["parent_station"])
+            .page_offset(0)
+            .page_limit(1)
+            .build(),
+        Stops::ALLOWED_QUERY_PARAMS
+    )]
+    #[case::trips(
+        TripsQuery::new()
+            .date(NaiveDate::from_ymd_opt(2022, 5, 8).expect("invalid date"))
+            .direction_id(0)
+            .route(["Red"])
+            .route_pattern(["Red-1-0"])
+            .id(["52417812"])
+            .name(["1234"])
+            .revenue(RevenueStatus::Revenue)
+            .sort(TripsSort::BikesAllowed, SortOrder::Ascending)
+            .include(["route"])
+            .page_offset(0)
+            .page_limit(1)
+            .build(),
+        Trips::ALLOWED_QUERY_PARAMS
+    )]
+    #[case::vehicles(
+        VehiclesQuery::new()
+            .id(["y1234"])
+            .trip(["52417812"])
+            .label(["1234"])
+            .route(["Red"])
+            .direction_id(0)
+            .route_type([RouteType::HeavyRail])
+            .revenue(RevenueStatus::Revenue)
+            .sort(VehiclesSort::Bearing, SortOrder::Ascending)
+            .include(["trip"])
+            .page_offset(0)
+            .page_limit(1)
+            .build(),
+        Vehicles::ALLOWED_QUERY_PARAMS
+    )]
+    fn test_query_keys_are_allowed(#[case] query: Result<Vec<(String, String)>, ClientError>, #[case] allowed: &[&str]) {
+        // Arrange
+        let query = query.expect("failed to build query");
+
+        // Act
+        let disallowed: Vec<&String> = query.iter().map(|(k, _)| k).filter(|k| !allowed.contains(&k.as_str())).collect();
+
+        // Assert
+        assert!(disallowed.is_empty(), "not in ALLOWED_QUERY_PARAMS: {:?}", disallowed);
+    }
+}
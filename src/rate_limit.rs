@@ -0,0 +1,214 @@
+//! Rate-limit awareness, based on the `x-ratelimit-*` headers of the V3 API.
+
+use std::time::Duration;
+
+use chrono::{DateTime, TimeZone, Utc};
+
+use super::*;
+
+/// Name of the header with the maximum number of requests per window.
+pub const RATE_LIMIT_LIMIT_HEADER: &str = "x-ratelimit-limit";
+
+/// Name of the header with the number of requests left in the current window.
+pub const RATE_LIMIT_REMAINING_HEADER: &str = "x-ratelimit-remaining";
+
+/// Name of the header with the time the current window resets, in seconds since the Unix epoch.
+pub const RATE_LIMIT_RESET_HEADER: &str = "x-ratelimit-reset";
+
+/// HTTP status code of responses to rate-limited requests.
+pub const TOO_MANY_REQUESTS: u16 = 429;
+
+/// Rate-limit state as reported by the API.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct RateLimit {
+    /// Maximum number of requests per window.
+    pub limit: u64,
+    /// Number of requests left in the current window.
+    pub remaining: u64,
+    /// When the current window resets.
+    pub reset_at: DateTime<Utc>,
+}
+
+impl RateLimit {
+    /// Parse the rate-limit state from response headers, if they are all present and valid.
+    ///
+    /// # Arguments
+    ///
+    /// * `headers` - pairings of header names to values
+    pub fn from_headers(headers: &[(String, String)]) -> Option<Self> {
+        let header = |name| find_header(headers, name).and_then(|value| value.trim().parse().ok());
+        Some(Self {
+            limit: header(RATE_LIMIT_LIMIT_HEADER)?,
+            remaining: header(RATE_LIMIT_REMAINING_HEADER)?,
+            reset_at: reset_at(headers)?,
+        })
+    }
+
+    /// Time left until the current window resets, or zero if it already has.
+    ///
+    /// # Arguments
+    ///
+    /// * `now` - the current time
+    pub fn until_reset(&self, now: DateTime<Utc>) -> Duration {
+        (self.reset_at - now).to_std().unwrap_or(Duration::ZERO)
+    }
+}
+
+/// Parse when the current rate-limit window resets from response headers, if present and valid.
+///
+/// # Arguments
+///
+/// * `headers` - pairings of header names to values
+pub(crate) fn reset_at(headers: &[(String, String)]) -> Option<DateTime<Utc>> {
+    let seconds = find_header(headers, RATE_LIMIT_RESET_HEADER)?.trim().parse().ok()?;
+    Utc.timestamp_opt(seconds, 0).single()
+}
+
+/// Default longest a [Client] sleeps for the rate limit before returning [ClientError::RateLimited] instead.
+pub const DEFAULT_MAX_RATE_LIMIT_WAIT: Duration = Duration::from_secs(60);
+
+/// What a [Client] does to stay under the rate limit.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum RateLimitPolicy {
+    /// Send requests right away and return [ClientError::RateLimited] once limited.
+    #[default]
+    Disabled,
+    /// Once no requests are left in the window, sleep until it resets before sending the next request.
+    /// A rate-limited request is retried once after sleeping until the reset, if the API said when that is.
+    WaitForReset {
+        /// Longest to sleep; if the window resets later than this, [ClientError::RateLimited] is returned instead.
+        max_wait: Duration,
+    },
+    /// Spread the remaining requests evenly over the rest of the window by sleeping before each request.
+    /// A rate-limited request is retried once after sleeping until the reset, if the API said when that is.
+    Spread {
+        /// Longest to sleep; if the window resets later than this, [ClientError::RateLimited] is returned instead.
+        max_wait: Duration,
+    },
+}
+
+impl RateLimitPolicy {
+    /// How long to sleep before sending the next request.
+    /// Fails with [ClientError::RateLimited] if no requests are left and the window resets later than the maximum wait.
+    ///
+    /// # Arguments
+    ///
+    /// * `state` - the most recent rate-limit state, if known
+    /// * `now` - the current time
+    pub fn delay(&self, state: Option<&RateLimit>, now: DateTime<Utc>) -> Result<Duration, ClientError> {
+        let state = match (self, state) {
+            (Self::Disabled, _) | (_, None) => return Ok(Duration::ZERO),
+            (_, Some(state)) => state,
+        };
+        match (self, state.remaining) {
+            (_, 0) => self.until_reset(state.reset_at, now),
+            (Self::Spread { .. }, remaining) => {
+                let delay = state.until_reset(now) / u32::try_from(remaining).unwrap_or(u32::MAX);
+                Ok(delay.min(self.max_wait()))
+            }
+            _ => Ok(Duration::ZERO),
+        }
+    }
+
+    /// How long to sleep until a rate-limit window resets.
+    /// Fails with [ClientError::RateLimited] if the window resets later than the maximum wait.
+    ///
+    /// # Arguments
+    ///
+    /// * `reset_at` - when the window resets
+    /// * `now` - the current time
+    pub fn until_reset(&self, reset_at: DateTime<Utc>, now: DateTime<Utc>) -> Result<Duration, ClientError> {
+        let wait = (reset_at - now).to_std().unwrap_or(Duration::ZERO);
+        if wait > self.max_wait() {
+            return Err(ClientError::RateLimited { reset_at: Some(reset_at) });
+        }
+        Ok(wait)
+    }
+
+    /// Longest the policy sleeps for the rate limit, which is zero when disabled.
+    pub fn max_wait(&self) -> Duration {
+        match self {
+            Self::Disabled => Duration::ZERO,
+            Self::WaitForReset { max_wait } | Self::Spread { max_wait } => *max_wait,
+        }
+    }
+
+    /// Whether a rate-limited request should be retried after sleeping until the reset.
+    pub fn retries_when_limited(&self) -> bool {
+        *self != Self::Disabled
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use rstest::*;
+
+    fn headers(limit: &str, remaining: &str, reset: &str) -> Vec<(String, String)> {
+        vec![
+            ("X-RateLimit-Limit".into(), limit.into()),
+            ("X-RateLimit-Remaining".into(), remaining.into()),
+            ("X-RateLimit-Reset".into(), reset.into()),
+        ]
+    }
+
+    fn state(remaining: u64, reset_in: i64) -> RateLimit {
+        RateLimit {
+            limit: 20,
+            remaining,
+            reset_at: Utc.timestamp_opt(1_000 + reset_in, 0).unwrap(),
+        }
+    }
+
+    fn wait_for_reset(max_wait: u64) -> RateLimitPolicy {
+        RateLimitPolicy::WaitForReset {
+            max_wait: Duration::from_secs(max_wait),
+        }
+    }
+
+    fn spread(max_wait: u64) -> RateLimitPolicy {
+        RateLimitPolicy::Spread {
+            max_wait: Duration::from_secs(max_wait),
+        }
+    }
+
+    #[rstest]
+    #[case::valid(headers("20", "19", "1650000000"), Some(RateLimit { limit: 20, remaining: 19, reset_at: Utc.timestamp_opt(1_650_000_000, 0).unwrap() }))]
+    #[case::missing(vec![("x-ratelimit-limit".into(), "20".into())], None)]
+    #[case::invalid(headers("20", "lots", "1650000000"), None)]
+    fn test_rate_limit_from_headers(#[case] headers: Vec<(String, String)>, #[case] expected: Option<RateLimit>) {
+        // Arrange
+
+        // Act
+        let actual = RateLimit::from_headers(&headers);
+
+        // Assert
+        assert_eq!(actual, expected);
+    }
+
+    #[rstest]
+    #[case::disabled(RateLimitPolicy::Disabled, Some(state(0, 30)), Some(Duration::ZERO))]
+    #[case::unknown_state(spread(60), None, Some(Duration::ZERO))]
+    #[case::wait_with_remaining(wait_for_reset(60), Some(state(5, 30)), Some(Duration::ZERO))]
+    #[case::wait_exhausted(wait_for_reset(60), Some(state(0, 30)), Some(Duration::from_secs(30)))]
+    #[case::wait_already_reset(wait_for_reset(60), Some(state(0, -5)), Some(Duration::ZERO))]
+    #[case::wait_too_long(wait_for_reset(10), Some(state(0, 30)), None)]
+    #[case::spread(spread(60), Some(state(5, 30)), Some(Duration::from_secs(6)))]
+    #[case::spread_capped(spread(2), Some(state(5, 30)), Some(Duration::from_secs(2)))]
+    #[case::spread_exhausted(spread(60), Some(state(0, 30)), Some(Duration::from_secs(30)))]
+    #[case::spread_too_long(spread(10), Some(state(0, 30)), None)]
+    fn test_rate_limit_policy_delay(#[case] policy: RateLimitPolicy, #[case] state: Option<RateLimit>, #[case] expected: Option<Duration>) {
+        // Arrange
+        let now = Utc.timestamp_opt(1_000, 0).unwrap();
+
+        // Act
+        let actual = policy.delay(state.as_ref(), now);
+
+        // Assert
+        match expected {
+            Some(expected) => assert_eq!(actual.expect("failed to get delay"), expected),
+            None => assert!(matches!(actual, Err(ClientError::RateLimited { reset_at }) if reset_at == state.map(|s| s.reset_at))),
+        }
+    }
+}
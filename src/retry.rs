@@ -0,0 +1,249 @@
+//! Retrying of requests that fail for transient reasons.
+
+use std::{
+    collections::hash_map::RandomState,
+    hash::{BuildHasher, Hasher},
+    time::Duration,
+};
+
+use super::*;
+
+/// Default delay before the first retry.
+pub const DEFAULT_BASE_DELAY: Duration = Duration::from_millis(250);
+
+/// Default cap on the delay between retries.
+pub const DEFAULT_MAX_DELAY: Duration = Duration::from_secs(10);
+
+/// How a [Client] retries requests that fail for transient reasons:
+/// transport errors, rate limiting (HTTP 429), and server errors (HTTP 5xx).
+/// Only idempotent requests are retried, which every request the client sends currently is.
+///
+/// The delay before each retry doubles from the base delay up to the maximum delay,
+/// and with jitter enabled (the default) is randomly picked from between half of that and all of it,
+/// so that many clients failing at once don't retry in lockstep.
+/// A request that was retried and still failed returns [ClientError::RetriesExhausted] with every attempt,
+/// however its final attempt failed.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct RetryPolicy {
+    /// Maximum number of attempts, including the first one.
+    max_attempts: u32,
+    /// Delay before the first retry.
+    base_delay: Duration,
+    /// Cap on the delay between retries.
+    max_delay: Duration,
+    /// Whether to randomize delays.
+    jitter: bool,
+}
+
+impl RetryPolicy {
+    /// Create a [RetryPolicy] that makes up to the given number of attempts, including the first one.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_attempts` - the maximum number of attempts, where 1 or less means never retrying
+    pub fn new(max_attempts: u32) -> Self {
+        Self {
+            max_attempts: max_attempts.max(1),
+            base_delay: DEFAULT_BASE_DELAY,
+            max_delay: DEFAULT_MAX_DELAY,
+            jitter: true,
+        }
+    }
+
+    /// Create a [RetryPolicy] that never retries, which is the default.
+    pub fn none() -> Self {
+        Self::new(1)
+    }
+
+    /// Set the delay before the first retry, which defaults to [DEFAULT_BASE_DELAY].
+    ///
+    /// # Arguments
+    ///
+    /// * `delay` - the delay before the first retry
+    pub fn with_base_delay(mut self, delay: Duration) -> Self {
+        self.base_delay = delay;
+        self
+    }
+
+    /// Set the cap on the delay between retries, which defaults to [DEFAULT_MAX_DELAY].
+    ///
+    /// # Arguments
+    ///
+    /// * `delay` - the maximum delay between retries
+    pub fn with_max_delay(mut self, delay: Duration) -> Self {
+        self.max_delay = delay;
+        self
+    }
+
+    /// Set whether delays are randomized, which they are by default.
+    ///
+    /// # Arguments
+    ///
+    /// * `jitter` - whether to randomize delays
+    pub fn with_jitter(mut self, jitter: bool) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    /// Maximum number of attempts, including the first one.
+    pub fn max_attempts(&self) -> u32 {
+        self.max_attempts
+    }
+
+    /// Whether another attempt may be made after a failed one.
+    ///
+    /// # Arguments
+    ///
+    /// * `attempt` - the number of the failed attempt, starting from 1
+    /// * `method` - the method of the request
+    pub fn should_retry(&self, attempt: u32, method: Method) -> bool {
+        attempt < self.max_attempts && method.is_idempotent()
+    }
+
+    /// Delay before retrying after a failed attempt.
+    ///
+    /// # Arguments
+    ///
+    /// * `attempt` - the number of the failed attempt, starting from 1
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let factor = 2u32.saturating_pow(attempt.saturating_sub(1));
+        let delay = self.base_delay.saturating_mul(factor).min(self.max_delay);
+        if !self.jitter {
+            return delay;
+        }
+        let half = delay / 2;
+        half + half.mul_f64(random_fraction())
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self::none()
+    }
+}
+
+/// Random number in `[0, 1)`, good enough for jitter, drawn from the random keys of the standard library's hasher.
+fn random_fraction() -> f64 {
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u8(0);
+    (hasher.finish() >> 11) as f64 / (1u64 << 53) as f64
+}
+
+/// What happened on a failed attempt at a request.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum AttemptOutcome {
+    /// The API responded with an unsuccessful status code.
+    Status(u16),
+    /// The request couldn't be completed, with the error message.
+    Error(String),
+}
+
+/// Failed attempt at a request that was retried.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct RetryAttempt {
+    /// What happened.
+    pub outcome: AttemptOutcome,
+    /// How long the client waited before the next attempt, [None] for the final attempt.
+    pub backoff: Option<Duration>,
+}
+
+/// Returns whether a request should be retried after this result, if the retry policy allows.
+///
+/// # Arguments
+///
+/// * `result` - the result of the attempt
+pub(crate) fn is_transient(result: &Result<HttpResponse, ClientError>) -> bool {
+    match result {
+        Ok(response) => response.status == TOO_MANY_REQUESTS || response.status >= 500,
+        Err(e) => e.is_retryable(),
+    }
+}
+
+/// Describe the outcome of a failed attempt.
+///
+/// # Arguments
+///
+/// * `result` - the result of the attempt
+pub(crate) fn attempt_outcome(result: &Result<HttpResponse, ClientError>) -> AttemptOutcome {
+    match result {
+        Ok(response) => AttemptOutcome::Status(response.status),
+        Err(e) => AttemptOutcome::Error(e.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use rstest::*;
+
+    #[rstest]
+    #[case::first(1, Duration::from_millis(100))]
+    #[case::second(2, Duration::from_millis(200))]
+    #[case::third(3, Duration::from_millis(400))]
+    #[case::capped(5, Duration::from_millis(1000))]
+    #[case::overflow(100, Duration::from_millis(1000))]
+    fn test_backoff(#[case] attempt: u32, #[case] expected: Duration) {
+        // Arrange
+        let policy = RetryPolicy::new(3)
+            .with_base_delay(Duration::from_millis(100))
+            .with_max_delay(Duration::from_secs(1))
+            .with_jitter(false);
+
+        // Act
+        let actual = policy.backoff(attempt);
+
+        // Assert
+        assert_eq!(actual, expected);
+    }
+
+    #[rstest]
+    fn test_backoff_jitter() {
+        // Arrange
+        let policy = RetryPolicy::new(3).with_base_delay(Duration::from_millis(100));
+
+        // Act
+        let actual: Vec<Duration> = (0..20).map(|_| policy.backoff(2)).collect();
+
+        // Assert
+        for delay in actual {
+            assert!(delay >= Duration::from_millis(100) && delay <= Duration::from_millis(200));
+        }
+    }
+
+    #[rstest]
+    #[case::retry_left(RetryPolicy::new(3), 2, Method::Get, true)]
+    #[case::no_retries_left(RetryPolicy::new(3), 3, Method::Get, false)]
+    #[case::never(RetryPolicy::none(), 1, Method::Head, false)]
+    #[case::zero_attempts(RetryPolicy::new(0), 1, Method::Get, false)]
+    fn test_should_retry(#[case] policy: RetryPolicy, #[case] attempt: u32, #[case] method: Method, #[case] expected: bool) {
+        // Arrange
+
+        // Act
+        let actual = policy.should_retry(attempt, method);
+
+        // Assert
+        assert_eq!(actual, expected);
+    }
+
+    #[rstest]
+    #[case::ok(200, false)]
+    #[case::not_found(404, false)]
+    #[case::rate_limited(429, true)]
+    #[case::server_error(500, true)]
+    #[case::unavailable(503, true)]
+    fn test_is_transient_status(#[case] status: u16, #[case] expected: bool) {
+        // Arrange
+        let result = Ok(HttpResponse {
+            status,
+            headers: vec![],
+            body: "".into(),
+        });
+
+        // Act
+        let actual = is_transient(&result);
+
+        // Assert
+        assert_eq!(actual, expected);
+    }
+}
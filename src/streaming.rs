@@ -0,0 +1,343 @@
+//! Streaming of server-sent events from the V3 API.
+//!
+//! When asked for `text/event-stream`, some endpoints keep the connection open and send
+//! a `reset` event with the full current state, followed by `add`, `update`, and `remove` events as the state changes.
+
+use std::{
+    fmt::{Debug, Formatter, Result as FmtResult},
+    io::BufRead,
+    marker::PhantomData,
+    thread,
+    time::Duration,
+};
+
+use serde::de::DeserializeOwned;
+
+use super::*;
+
+/// Default delay before reconnecting to a stream.
+pub const DEFAULT_RECONNECT_DELAY: Duration = Duration::from_secs(1);
+
+/// Default number of consecutive failed connections before a stream gives up.
+pub const DEFAULT_MAX_RECONNECTS: u32 = 5;
+
+/// Typed event from a stream of server-sent events.
+#[derive(Debug, PartialEq, Clone)]
+pub enum Event<T: Identified> {
+    /// The full current state, replacing everything received so far.
+    Reset(Vec<T>),
+    /// A newly added data model.
+    Add(T),
+    /// A new version of an existing data model.
+    Update(T),
+    /// A removed data model, identified by its type and strongly typed ID.
+    Remove(RelationshipAtom<T::Id>),
+    /// A keep-alive sent by the server while nothing has changed.
+    KeepAlive,
+}
+
+/// Raw server-sent event, before its data is parsed.
+#[derive(Debug, PartialEq, Clone, Default)]
+struct RawEvent {
+    /// Event name.
+    event: String,
+    /// Event data, with multiple data lines joined by newlines.
+    data: String,
+}
+
+/// Iterator over the events of a streaming endpoint.
+///
+/// The connection is opened lazily on the first call to [Iterator::next].
+/// Whenever the connection drops, the stream waits and reconnects, sending the ID of the last event received
+/// as the `Last-Event-ID` header if the server gave one; the server then usually starts over with a [Event::Reset].
+/// Errors are yielded without ending the stream, except that the stream ends after too many consecutive failed connections.
+pub struct EventStream<'a, T> {
+    /// Client that opens the connections.
+    client: &'a Client,
+    /// Endpoint being streamed.
+    endpoint: String,
+    /// Query parameters sent with every connection.
+    query_params: Vec<(String, String)>,
+    /// Body of the open connection, if any.
+    reader: Option<Box<dyn BufRead + Send>>,
+    /// ID of the last event received, if the server sent one.
+    last_event_id: Option<String>,
+    /// Delay before reconnecting.
+    reconnect_delay: Duration,
+    /// Number of consecutive failed connections before giving up.
+    max_reconnects: u32,
+    /// Number of consecutive failed connections so far.
+    failures: u32,
+    /// Whether any connection has been attempted yet.
+    connected_before: bool,
+    /// Whether any event has been received on the open connection.
+    received: bool,
+    /// Data type of each event.
+    data: PhantomData<T>,
+}
+
+impl<'a, T> EventStream<'a, T> {
+    /// Create a new [EventStream]. Presumes that all query parameters given are valid.
+    ///
+    /// # Arguments
+    ///
+    /// * `client` - the client that opens the connections
+    /// * `endpoint` - the endpoint to stream
+    /// * `query_params` - a slice of pairings of query parameter names to values
+    pub(crate) fn new<K: AsRef<str>, V: AsRef<str>>(client: &'a Client, endpoint: &str, query_params: &[(K, V)]) -> Self {
+        Self {
+            client,
+            endpoint: endpoint.into(),
+            query_params: query_params.iter().map(|(k, v)| (k.as_ref().to_string(), v.as_ref().to_string())).collect(),
+            reader: None,
+            last_event_id: None,
+            reconnect_delay: DEFAULT_RECONNECT_DELAY,
+            max_reconnects: DEFAULT_MAX_RECONNECTS,
+            failures: 0,
+            connected_before: false,
+            received: false,
+            data: PhantomData,
+        }
+    }
+
+    /// Set the delay before reconnecting, which defaults to [DEFAULT_RECONNECT_DELAY].
+    /// The server can still change the delay with a `retry` field.
+    ///
+    /// # Arguments
+    ///
+    /// * `delay` - the delay before reconnecting
+    pub fn with_reconnect_delay(mut self, delay: Duration) -> Self {
+        self.reconnect_delay = delay;
+        self
+    }
+
+    /// Set the number of consecutive failed connections before the stream ends, which defaults to [DEFAULT_MAX_RECONNECTS].
+    /// A connection fails if it can't be opened or if it closes before sending any event.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_reconnects` - the number of consecutive failed connections to allow
+    pub fn with_max_reconnects(mut self, max_reconnects: u32) -> Self {
+        self.max_reconnects = max_reconnects;
+        self
+    }
+
+    /// ID of the last event received, which is sent when reconnecting.
+    pub fn last_event_id(&self) -> Option<&str> {
+        self.last_event_id.as_deref()
+    }
+
+    /// Read the next raw event from the open connection.
+    /// Returns [None] and drops the connection once it closes or fails.
+    fn read_event(&mut self) -> Option<RawEvent> {
+        let reader = self.reader.as_mut()?;
+        let mut event = RawEvent::default();
+        let mut has_data = false;
+        loop {
+            let mut line = String::new();
+            match reader.read_line(&mut line) {
+                Ok(0) | Err(_) => {
+                    self.reader = None;
+                    return None;
+                }
+                Ok(_) => {}
+            }
+            let line = line.trim_end_matches(['\r', '\n']);
+            if line.is_empty() {
+                if has_data || !event.event.is_empty() {
+                    return Some(event);
+                }
+                continue;
+            }
+            if line.starts_with(':') {
+                if !has_data && event.event.is_empty() {
+                    return Some(RawEvent {
+                        event: "keep-alive".into(),
+                        data: String::new(),
+                    });
+                }
+                continue;
+            }
+            let (field, value) = match line.split_once(':') {
+                Some((field, value)) => (field, value.strip_prefix(' ').unwrap_or(value)),
+                None => (line, ""),
+            };
+            match field {
+                "event" => event.event = value.into(),
+                "data" => {
+                    if has_data {
+                        event.data.push('\n');
+                    }
+                    event.data.push_str(value);
+                    has_data = true;
+                }
+                "id" => self.last_event_id = Some(value.into()),
+                "retry" => {
+                    if let Ok(millis) = value.parse() {
+                        self.reconnect_delay = Duration::from_millis(millis);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+impl<'a, T: DeserializeOwned + Identified<Id: DeserializeOwned>> EventStream<'a, T> {
+    /// Turn a raw event into a typed event, or [None] if the event isn't one of the known kinds.
+    ///
+    /// # Arguments
+    ///
+    /// * `endpoint` - the endpoint the event came from
+    /// * `raw` - the raw event
+    fn parse_event(endpoint: &str, raw: RawEvent) -> Option<Result<Event<T>, ClientError>> {
+        let event = match raw.event.as_str() {
+            "reset" => deserialize(endpoint, &raw.data).map(Event::Reset),
+            "add" => deserialize(endpoint, &raw.data).map(Event::Add),
+            "update" => deserialize(endpoint, &raw.data).map(Event::Update),
+            "remove" => deserialize(endpoint, &raw.data).map(Event::Remove),
+            "keep-alive" => Ok(Event::KeepAlive),
+            _ => return None,
+        };
+        Some(event)
+    }
+}
+
+impl<'a, T: DeserializeOwned + Identified<Id: DeserializeOwned>> Iterator for EventStream<'a, T> {
+    type Item = Result<Event<T>, ClientError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if self.reader.is_none() {
+                if self.connected_before && !self.received {
+                    self.failures += 1;
+                }
+                if self.failures > self.max_reconnects {
+                    return None;
+                }
+                if self.connected_before {
+                    thread::sleep(self.reconnect_delay);
+                }
+                self.connected_before = true;
+                self.received = false;
+                match self.client.open_stream(&self.endpoint, &self.query_params, self.last_event_id.as_deref()) {
+                    Ok(stream) => self.reader = Some(stream.body),
+                    Err(e) => return Some(Err(e)),
+                }
+            }
+            if let Some(raw) = self.read_event() {
+                self.received = true;
+                self.failures = 0;
+                if let Some(event) = Self::parse_event(&self.endpoint, raw) {
+                    return Some(event);
+                }
+            }
+        }
+    }
+}
+
+impl<'a, T> Debug for EventStream<'a, T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        f.debug_struct("EventStream")
+            .field("client", &self.client)
+            .field("endpoint", &self.endpoint)
+            .field("query_params", &self.query_params)
+            .field("connected", &self.reader.is_some())
+            .field("last_event_id", &self.last_event_id)
+            .field("reconnect_delay", &self.reconnect_delay)
+            .field("max_reconnects", &self.max_reconnects)
+            .field("failures", &self.failures)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::io::Cursor;
+
+    use rstest::*;
+
+    fn stream_of<'a>(client: &'a Client, body: &str) -> EventStream<'a, Vehicle> {
+        let mut stream = EventStream::new::<String, String>(client, "vehicles", &[]);
+        stream.reader = Some(Box::new(Cursor::new(body.to_string().into_bytes())));
+        stream
+    }
+
+    #[rstest]
+    #[case::simple("event: add\ndata: {}\n\n", "add", "{}")]
+    #[case::no_space("event:add\ndata:{}\n\n", "add", "{}")]
+    #[case::crlf("event: update\r\ndata: {}\r\n\r\n", "update", "{}")]
+    #[case::multiline_data("event: reset\ndata: [\ndata: ]\n\n", "reset", "[\n]")]
+    #[case::keep_alive_comment(": keep-alive\n\n", "keep-alive", "")]
+    #[case::leading_blank_lines("\n\nevent: remove\ndata: {}\n\n", "remove", "{}")]
+    fn test_read_event(#[case] body: &str, #[case] expected_event: &str, #[case] expected_data: &str) {
+        // Arrange
+        let client = Client::without_key();
+        let mut stream = stream_of(&client, body);
+
+        // Act
+        let actual = stream.read_event();
+
+        // Assert
+        assert_eq!(
+            actual,
+            Some(RawEvent {
+                event: expected_event.into(),
+                data: expected_data.into()
+            })
+        );
+    }
+
+    #[rstest]
+    fn test_read_event_fields() {
+        // Arrange
+        let client = Client::without_key();
+        let mut stream = stream_of(&client, "id: 42\nretry: 250\nevent: add\ndata: {}\n\n");
+
+        // Act
+        stream.read_event();
+        let after_end = stream.read_event();
+
+        // Assert
+        assert_eq!(stream.last_event_id(), Some("42"));
+        assert_eq!(stream.reconnect_delay, Duration::from_millis(250));
+        assert_eq!(after_end, None);
+        assert!(stream.reader.is_none());
+    }
+
+    #[rstest]
+    #[case::reset("reset", "[]", true)]
+    #[case::remove("remove", "{\"type\": \"vehicle\", \"id\": \"y1234\"}", true)]
+    #[case::keep_alive("keep-alive", "", true)]
+    #[case::bad_json("add", "{", false)]
+    fn test_parse_event(#[case] event: &str, #[case] data: &str, #[case] expected_ok: bool) {
+        // Arrange
+        let raw = RawEvent {
+            event: event.into(),
+            data: data.into(),
+        };
+
+        // Act
+        let actual = EventStream::<Vehicle>::parse_event("vehicles", raw).expect("unknown event");
+
+        // Assert
+        assert_eq!(actual.is_ok(), expected_ok);
+    }
+
+    #[rstest]
+    fn test_parse_unknown_event() {
+        // Arrange
+        let raw = RawEvent {
+            event: "foobar".into(),
+            data: "{}".into(),
+        };
+
+        // Act
+        let actual = EventStream::<Vehicle>::parse_event("vehicles", raw);
+
+        // Assert
+        assert!(actual.is_none());
+    }
+}
@@ -0,0 +1,313 @@
+//! Pluggable HTTP transport that the client sends its requests through.
+
+use std::{
+    fmt::{Debug, Display, Formatter, Result as FmtResult},
+    io::{BufRead, BufReader, Cursor, Read},
+};
+
+use ureq::{Agent, AgentBuilder, Error as RequestError};
+
+use super::*;
+
+/// HTTP request method.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Method {
+    /// `GET` request.
+    Get,
+    /// `HEAD` request.
+    Head,
+}
+
+impl Method {
+    /// Returns whether sending the request more than once has the same effect as sending it once, so it is safe to retry.
+    pub fn is_idempotent(&self) -> bool {
+        matches!(self, Self::Get | Self::Head)
+    }
+}
+
+impl Display for Method {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            Self::Get => write!(f, "GET"),
+            Self::Head => write!(f, "HEAD"),
+        }
+    }
+}
+
+/// HTTP request for a [Transport] to send.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct HttpRequest {
+    /// Request method.
+    pub method: Method,
+    /// Full request URL, without the query string.
+    pub url: String,
+    /// Request headers as pairings of names to values.
+    pub headers: Vec<(String, String)>,
+    /// Query parameters as pairings of names to values, not yet percent-encoded.
+    pub query: Vec<(String, String)>,
+}
+
+impl HttpRequest {
+    /// Returns the value of the first header with the given name, ignoring case.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - the header name
+    pub fn header(&self, name: &str) -> Option<&str> {
+        find_header(&self.headers, name)
+    }
+}
+
+/// HTTP response returned by a [Transport].
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct HttpResponse {
+    /// Response status code.
+    pub status: u16,
+    /// Response headers as pairings of names to values.
+    pub headers: Vec<(String, String)>,
+    /// Response body.
+    pub body: String,
+}
+
+impl HttpResponse {
+    /// Returns the value of the first header with the given name, ignoring case.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - the header name
+    pub fn header(&self, name: &str) -> Option<&str> {
+        find_header(&self.headers, name)
+    }
+
+    /// Returns whether the status code is in the 2xx range.
+    pub fn is_success(&self) -> bool {
+        (200..300).contains(&self.status)
+    }
+}
+
+/// HTTP response returned by a [Transport] whose body is read incrementally, such as a stream of server-sent events.
+pub struct HttpStream {
+    /// Response status code.
+    pub status: u16,
+    /// Response headers as pairings of names to values.
+    pub headers: Vec<(String, String)>,
+    /// Response body, read as it arrives.
+    pub body: Box<dyn BufRead + Send>,
+}
+
+impl HttpStream {
+    /// Returns the value of the first header with the given name, ignoring case.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - the header name
+    pub fn header(&self, name: &str) -> Option<&str> {
+        find_header(&self.headers, name)
+    }
+
+    /// Returns whether the status code is in the 2xx range.
+    pub fn is_success(&self) -> bool {
+        (200..300).contains(&self.status)
+    }
+
+    /// Read the rest of the body and turn the stream into a complete [HttpResponse].
+    pub fn into_response(mut self) -> Result<HttpResponse, ClientError> {
+        let mut body = String::new();
+        self.body.read_to_string(&mut body)?;
+        Ok(HttpResponse {
+            status: self.status,
+            headers: self.headers,
+            body,
+        })
+    }
+}
+
+impl Debug for HttpStream {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        f.debug_struct("HttpStream")
+            .field("status", &self.status)
+            .field("headers", &self.headers)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Find the value of the first header with a given name, ignoring case.
+pub(crate) fn find_header<'a>(headers: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    headers.iter().find(|(k, _)| k.eq_ignore_ascii_case(name)).map(|(_, v)| v.as_str())
+}
+
+/// Something that can send HTTP requests on behalf of a [Client], such as a real HTTP stack, a proxy, or an in-memory mock.
+///
+/// Non-2xx responses should be returned as an [HttpResponse] rather than an error;
+/// errors are reserved for requests that couldn't be completed at all.
+pub trait Transport: Debug + Send + Sync {
+    /// Send a request and return its response.
+    ///
+    /// # Arguments
+    ///
+    /// * `request` - the request to send
+    fn send(&self, request: &HttpRequest) -> Result<HttpResponse, ClientError>;
+
+    /// Send a request and return its response without waiting for the whole body,
+    /// which is needed for long-lived responses such as streams of server-sent events.
+    ///
+    /// By default, this sends the request through [Transport::send] and reads from the complete body,
+    /// which is enough for transports that don't talk to a real server.
+    ///
+    /// # Arguments
+    ///
+    /// * `request` - the request to send
+    fn stream(&self, request: &HttpRequest) -> Result<HttpStream, ClientError> {
+        let response = self.send(request)?;
+        Ok(HttpStream {
+            status: response.status,
+            headers: response.headers,
+            body: Box::new(Cursor::new(response.body.into_bytes())),
+        })
+    }
+}
+
+/// Maximum number of idle connections kept open per host by the default [UreqTransport].
+pub const DEFAULT_MAX_IDLE_CONNECTIONS_PER_HOST: usize = 8;
+
+/// Default [Transport] built on the [ureq] crate.
+///
+/// Requests go through a [ureq::Agent], which keeps connections alive and reuses them across requests,
+/// so fanning out many requests to the API doesn't pay for a new connection and TLS handshake each time.
+/// Cloning the transport shares the agent and its connection pool.
+#[derive(Debug, Clone)]
+pub struct UreqTransport {
+    /// Agent that holds the connection pool.
+    agent: Agent,
+}
+
+impl UreqTransport {
+    /// Create a [UreqTransport] with a new connection pool,
+    /// keeping up to [DEFAULT_MAX_IDLE_CONNECTIONS_PER_HOST] idle connections open to the API.
+    pub fn new() -> Self {
+        Self::with_agent(AgentBuilder::new().max_idle_connections_per_host(DEFAULT_MAX_IDLE_CONNECTIONS_PER_HOST).build())
+    }
+
+    /// Create a [UreqTransport] from an already configured agent, such as one shared with other parts of a program.
+    ///
+    /// # Arguments
+    ///
+    /// * `agent` - the agent to send requests through
+    pub fn with_agent(agent: Agent) -> Self {
+        Self { agent }
+    }
+}
+
+impl Default for UreqTransport {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Transport for UreqTransport {
+    fn send(&self, request: &HttpRequest) -> Result<HttpResponse, ClientError> {
+        self.stream(request)?.into_response()
+    }
+
+    fn stream(&self, request: &HttpRequest) -> Result<HttpStream, ClientError> {
+        let ureq_request = self.agent.request(&request.method.to_string(), &request.url);
+        let ureq_request = request.headers.iter().fold(ureq_request, |r, (k, v)| r.set(k, v));
+        let ureq_request = request.query.iter().fold(ureq_request, |r, (k, v)| r.query(k, v));
+        let response = match ureq_request.call() {
+            Ok(response) => response,
+            Err(RequestError::Status(_, response)) => response,
+            Err(RequestError::Transport(err)) => return Err(ClientError::from(err)),
+        };
+        let status = response.status();
+        let headers = response
+            .headers_names()
+            .into_iter()
+            .filter_map(|name| response.header(&name).map(|value| (name.clone(), value.to_string())))
+            .collect();
+        Ok(HttpStream {
+            status,
+            headers,
+            body: Box::new(BufReader::new(response.into_reader())),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use rstest::*;
+
+    #[rstest]
+    #[case::exact_case("x-api-key", Some("foo"))]
+    #[case::different_case("X-Api-Key", Some("foo"))]
+    #[case::missing("accept", None)]
+    fn test_http_response_header(#[case] name: &str, #[case] expected: Option<&str>) {
+        // Arrange
+        let response = HttpResponse {
+            status: 200,
+            headers: vec![("x-api-key".into(), "foo".into()), ("X-API-KEY".into(), "bar".into())],
+            body: "".into(),
+        };
+
+        // Act
+        let actual = response.header(name);
+
+        // Assert
+        assert_eq!(actual, expected);
+    }
+
+    #[rstest]
+    #[case::ok(200, true)]
+    #[case::no_content(204, true)]
+    #[case::not_modified(304, false)]
+    #[case::not_found(404, false)]
+    fn test_http_response_is_success(#[case] status: u16, #[case] expected: bool) {
+        // Arrange
+        let response = HttpResponse {
+            status,
+            headers: vec![],
+            body: "".into(),
+        };
+
+        // Act
+        let actual = response.is_success();
+
+        // Assert
+        assert_eq!(actual, expected);
+    }
+
+    #[rstest]
+    fn test_default_stream() {
+        // Arrange
+        #[derive(Debug)]
+        struct FixedTransport;
+
+        impl Transport for FixedTransport {
+            fn send(&self, _request: &HttpRequest) -> Result<HttpResponse, ClientError> {
+                Ok(HttpResponse {
+                    status: 200,
+                    headers: vec![("content-type".into(), "text/event-stream".into())],
+                    body: "event: reset\ndata: []\n\n".into(),
+                })
+            }
+        }
+        let request = HttpRequest {
+            method: Method::Get,
+            url: "http://localhost/vehicles".into(),
+            headers: vec![],
+            query: vec![],
+        };
+
+        // Act
+        let mut stream = FixedTransport.stream(&request).expect("failed to open stream");
+        let mut first_line = String::new();
+        stream.body.read_line(&mut first_line).expect("failed to read line");
+        let response = stream.into_response().expect("failed to read body");
+
+        // Assert
+        assert_eq!(first_line, "event: reset\n");
+        assert_eq!(response.header("Content-Type"), Some("text/event-stream"));
+        assert_eq!(response.body, "data: []\n\n");
+    }
+}
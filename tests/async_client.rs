@@ -0,0 +1,126 @@
+//! Offline testing of the asynchronous client, without depending on any particular `async` runtime.
+#![cfg(feature = "async")]
+
+use std::{
+    future::Future,
+    pin::pin,
+    sync::{Arc, Mutex},
+    task::{Context, Poll, Wake},
+    thread::{self, Thread},
+};
+
+use mbta_rs::*;
+use rstest::*;
+
+/// Waker that unparks the thread blocking on a future.
+struct ThreadWaker(Thread);
+
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+/// Minimal executor that blocks the current thread until a future completes.
+fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = pin!(future);
+    let waker = Arc::new(ThreadWaker(thread::current())).into();
+    let mut cx = Context::from_waker(&waker);
+    loop {
+        match future.as_mut().poll(&mut cx) {
+            Poll::Ready(output) => return output,
+            Poll::Pending => thread::park(),
+        }
+    }
+}
+
+/// Blocking transport that answers every request with the same response and records every request it sends.
+#[derive(Debug, Clone)]
+struct FixedTransport {
+    /// Response to every request.
+    response: HttpResponse,
+    /// Requests sent so far.
+    requests: Arc<Mutex<Vec<HttpRequest>>>,
+}
+
+impl FixedTransport {
+    fn new(status: u16, body: &str) -> Self {
+        Self {
+            response: HttpResponse {
+                status,
+                headers: vec![],
+                body: body.into(),
+            },
+            requests: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+}
+
+impl Transport for FixedTransport {
+    fn send(&self, request: &HttpRequest) -> Result<HttpResponse, ClientError> {
+        self.requests.lock().expect("poisoned lock").push(request.clone());
+        Ok(self.response.clone())
+    }
+}
+
+/// Asynchronous transport that answers immediately, without a background thread.
+#[derive(Debug, Clone)]
+struct ReadyTransport(FixedTransport);
+
+impl AsyncTransport for ReadyTransport {
+    fn send<'a>(&'a self, request: &'a HttpRequest) -> TransportFuture<'a> {
+        Box::pin(async move { self.0.send(request) })
+    }
+}
+
+const LINE: &str = "{\"data\": {\"type\": \"line\", \"id\": \"line-Red\", \"attributes\": {\"color\": \"DA291C\", \"text_color\": \"FFFFFF\", \"sort_order\": 10010, \"short_name\": \"\", \"long_name\": \"Red Line\"}}, \"jsonapi\": {\"version\": \"1.0\"}}";
+
+#[rstest]
+fn test_threaded_transport() {
+    // Arrange
+    let transport = FixedTransport::new(200, LINE);
+    let client = AsyncClient::with_key("test key").with_transport(ThreadedTransport::new(transport.clone()));
+
+    // Act
+    let line = block_on(client.line("line-Red")).expect("failed to get line");
+
+    // Assert
+    let requests = transport.requests.lock().expect("poisoned lock");
+    assert_eq!(line.data.attributes.long_name, "Red Line");
+    assert_eq!(requests[0].url, "https://api-v3.mbta.com/lines/line-Red");
+    assert_eq!(requests[0].header("x-api-key"), Some("test key"));
+}
+
+#[rstest]
+fn test_custom_async_transport() {
+    // Arrange
+    let transport = FixedTransport::new(200, "{\"data\": [], \"jsonapi\": {\"version\": \"1.0\"}}");
+    let client = AsyncClient::without_key().with_transport(ReadyTransport(transport.clone()));
+
+    // Act
+    let lines = block_on(client.lines(&[("page[limit]", "3")])).expect("failed to get lines");
+
+    // Assert
+    let requests = transport.requests.lock().expect("poisoned lock");
+    assert!(lines.data.is_empty());
+    assert_eq!(requests[0].query, vec![("page[limit]".to_string(), "3".to_string())]);
+}
+
+#[rstest]
+fn test_async_errors() {
+    // Arrange
+    let transport = FixedTransport::new(
+        404,
+        "{\"errors\": [{\"status\": \"404\", \"code\": \"not_found\"}], \"jsonapi\": {\"version\": \"1.0\"}}",
+    );
+    let client = AsyncClient::without_key().with_transport(ReadyTransport(transport.clone()));
+
+    // Act
+    let query_param_error = block_on(client.lines(&[("foo", "bar")])).expect_err("lines did not fail");
+    let response_error = block_on(client.line("foobar")).expect_err("line did not fail");
+
+    // Assert
+    assert!(matches!(query_param_error, ClientError::InvalidQueryParam { .. }));
+    assert!(response_error.is_not_found());
+    assert_eq!(transport.requests.lock().expect("poisoned lock").len(), 1);
+}
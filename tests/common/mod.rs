@@ -0,0 +1,70 @@
+//! Shared helpers for offline tests that need a real (local) HTTP server.
+#![allow(dead_code)]
+
+use std::{
+    io::{BufRead, BufReader, Write},
+    net::TcpListener,
+    sync::{Arc, Mutex},
+    thread,
+};
+
+/// Local HTTP server that answers each connection with the next scripted raw response, then closes it.
+/// Once the script runs out the server stops listening, so further connections are refused.
+#[derive(Debug)]
+pub struct TestServer {
+    /// Base URL of the server.
+    pub url: String,
+    /// Raw heads of the requests received so far.
+    requests: Arc<Mutex<Vec<String>>>,
+}
+
+impl TestServer {
+    /// Start a server on a random local port.
+    ///
+    /// # Arguments
+    ///
+    /// * `responses` - the raw HTTP responses to send, one per connection
+    pub fn new(responses: Vec<String>) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("failed to bind test server");
+        let url = format!("http://{}", listener.local_addr().expect("failed to get test server address"));
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let server_requests = requests.clone();
+        thread::spawn(move || {
+            for response in responses {
+                let (mut stream, _) = match listener.accept() {
+                    Ok(connection) => connection,
+                    Err(_) => return,
+                };
+                let mut reader = BufReader::new(stream.try_clone().expect("failed to clone connection"));
+                let mut head = String::new();
+                loop {
+                    let mut line = String::new();
+                    if reader.read_line(&mut line).unwrap_or(0) == 0 || line == "\r\n" {
+                        break;
+                    }
+                    head.push_str(&line);
+                }
+                server_requests.lock().expect("poisoned lock").push(head);
+                let _ = stream.write_all(response.as_bytes());
+            }
+        });
+        Self { url, requests }
+    }
+
+    /// Raw heads of the requests received so far, in order.
+    pub fn requests(&self) -> Vec<String> {
+        self.requests.lock().expect("poisoned lock").clone()
+    }
+}
+
+/// Build a raw HTTP response that ends when the connection closes.
+///
+/// # Arguments
+///
+/// * `status` - the status line after the HTTP version, such as `"200 OK"`
+/// * `headers` - pairings of header names to values
+/// * `body` - the response body
+pub fn http_response(status: &str, headers: &[(&str, &str)], body: &str) -> String {
+    let headers: String = headers.iter().map(|(k, v)| format!("{}: {}\r\n", k, v)).collect();
+    format!("HTTP/1.1 {}\r\n{}connection: close\r\n\r\n{}", status, headers, body)
+}
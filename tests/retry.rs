@@ -0,0 +1,203 @@
+//! Offline testing of retries against a local, failing stand-in server.
+
+mod common;
+
+use std::time::Duration;
+
+use common::*;
+use mbta_rs::*;
+use rstest::*;
+
+const LINES: &str = "{\"data\": [{\"type\": \"line\", \"id\": \"line-Red\", \"attributes\": {\"color\": \"DA291C\", \"text_color\": \"FFFFFF\", \"sort_order\": 10010, \"short_name\": \"\", \"long_name\": \"Red Line\"}}], \"jsonapi\": {\"version\": \"1.0\"}}";
+
+const NOT_FOUND: &str = "{\"errors\": [{\"status\": \"404\", \"code\": \"not_found\"}], \"jsonapi\": {\"version\": \"1.0\"}}";
+
+fn unavailable() -> String {
+    http_response("503 Service Unavailable", &[("content-type", "text/html")], "<html>unavailable</html>")
+}
+
+fn ok(body: &str) -> String {
+    http_response("200 OK", &[("content-type", "application/vnd.api+json")], body)
+}
+
+fn client(server: &TestServer, max_attempts: u32) -> Client {
+    Client::with_url(&server.url)
+        .with_retry_policy(RetryPolicy::new(max_attempts).with_base_delay(Duration::from_millis(5)).with_jitter(false))
+}
+
+#[rstest]
+fn test_retry_until_success() {
+    // Arrange
+    let server = TestServer::new(vec![unavailable(), unavailable(), ok(LINES)]);
+    let client = client(&server, 3);
+
+    // Act
+    let lines = client.lines(&[("filter[id]", "line-Red")]).expect("failed to get lines");
+
+    // Assert
+    let requests = server.requests();
+    assert_eq!(lines.data.len(), 1);
+    assert_eq!(requests.len(), 3);
+    assert!(requests.iter().all(|r| r.starts_with("GET /lines?filter%5Bid%5D=line-Red HTTP/1.1")));
+}
+
+#[rstest]
+fn test_retries_exhausted() {
+    // Arrange
+    let server = TestServer::new(vec![unavailable(), unavailable(), unavailable()]);
+    let client = client(&server, 3);
+
+    // Act
+    let error = client.lines::<String, String>(&[]).expect_err("lines did not fail");
+
+    // Assert
+    assert_eq!(server.requests().len(), 3);
+    match error {
+        ClientError::RetriesExhausted { attempts, source } => {
+            assert_eq!(
+                attempts,
+                vec![
+                    RetryAttempt {
+                        outcome: AttemptOutcome::Status(503),
+                        backoff: Some(Duration::from_millis(5))
+                    },
+                    RetryAttempt {
+                        outcome: AttemptOutcome::Status(503),
+                        backoff: Some(Duration::from_millis(10))
+                    },
+                    RetryAttempt {
+                        outcome: AttemptOutcome::Status(503),
+                        backoff: None
+                    },
+                ]
+            );
+            assert!(matches!(*source, ClientError::ServerError { status: 503, .. }));
+        }
+        _ => panic!("wrong error type"),
+    }
+}
+
+#[rstest]
+fn test_retry_transport_errors() {
+    // Arrange
+    let server = TestServer::new(vec![unavailable()]);
+    let client = client(&server, 3);
+
+    // Act
+    let error = client.lines::<String, String>(&[]).expect_err("lines did not fail");
+
+    // Assert
+    assert_eq!(server.requests().len(), 1);
+    match error {
+        ClientError::RetriesExhausted { attempts, source } => {
+            assert_eq!(attempts.len(), 3);
+            assert_eq!(attempts[0].outcome, AttemptOutcome::Status(503));
+            assert!(matches!(attempts[1].outcome, AttemptOutcome::Error(_)));
+            assert!(matches!(*source, ClientError::TransportError(_)));
+        }
+        _ => panic!("wrong error type"),
+    }
+}
+
+#[rstest]
+fn test_no_retry_for_client_errors() {
+    // Arrange
+    let server = TestServer::new(vec![
+        http_response("404 Not Found", &[("content-type", "application/vnd.api+json")], NOT_FOUND),
+        ok(LINES),
+    ]);
+    let client = client(&server, 3);
+
+    // Act
+    let error = client.line("foobar").expect_err("line did not fail");
+
+    // Assert
+    assert_eq!(server.requests().len(), 1);
+    assert!(error.is_not_found());
+}
+
+#[rstest]
+fn test_retry_until_client_error() {
+    // Arrange
+    let server = TestServer::new(vec![
+        unavailable(),
+        http_response("404 Not Found", &[("content-type", "application/vnd.api+json")], NOT_FOUND),
+    ]);
+    let client = client(&server, 3);
+
+    // Act
+    let error = client.line("foobar").expect_err("line did not fail");
+
+    // Assert
+    assert_eq!(server.requests().len(), 2);
+    assert!(error.is_not_found());
+    match error {
+        ClientError::RetriesExhausted { attempts, source } => {
+            assert_eq!(
+                attempts,
+                vec![
+                    RetryAttempt {
+                        outcome: AttemptOutcome::Status(503),
+                        backoff: Some(Duration::from_millis(5))
+                    },
+                    RetryAttempt {
+                        outcome: AttemptOutcome::Status(404),
+                        backoff: None
+                    },
+                ]
+            );
+            assert!(matches!(*source, ClientError::NotFound { .. }));
+        }
+        _ => panic!("wrong error type"),
+    }
+}
+
+#[rstest]
+fn test_retry_until_rate_limited() {
+    // Arrange
+    let reset = (chrono::Utc::now().timestamp() + 3_600).to_string();
+    let server = TestServer::new(vec![
+        unavailable(),
+        http_response(
+            "429 Too Many Requests",
+            &[
+                ("x-ratelimit-limit", "20"),
+                ("x-ratelimit-remaining", "0"),
+                ("x-ratelimit-reset", &reset),
+            ],
+            "",
+        ),
+    ]);
+    let client = client(&server, 3).with_rate_limit_policy(RateLimitPolicy::WaitForReset {
+        max_wait: Duration::from_secs(5),
+    });
+
+    // Act
+    let error = client.lines::<String, String>(&[]).expect_err("lines did not fail");
+
+    // Assert
+    assert_eq!(server.requests().len(), 2);
+    match error {
+        ClientError::RetriesExhausted { attempts, source } => {
+            assert_eq!(attempts.len(), 2);
+            assert_eq!(attempts[0].outcome, AttemptOutcome::Status(503));
+            assert!(matches!(attempts[1].outcome, AttemptOutcome::Error(_)));
+            assert!(matches!(*source, ClientError::RateLimited { reset_at: Some(_) }));
+        }
+        _ => panic!("wrong error type"),
+    }
+}
+
+#[rstest]
+fn test_no_retry_by_default() {
+    // Arrange
+    let server = TestServer::new(vec![unavailable(), ok(LINES)]);
+    let client = Client::with_url(&server.url);
+
+    // Act
+    let error = client.lines::<String, String>(&[]).expect_err("lines did not fail");
+
+    // Assert
+    assert_eq!(server.requests().len(), 1);
+    assert!(matches!(error, ClientError::ServerError { status: 503, .. }));
+}
@@ -3,7 +3,7 @@
 /// Macro for creating sanity tests for endpoints with plural return values and single return values.
 #[macro_export]
 macro_rules! test_endpoint_plural_and_singular {
-    (plural_func=$plural_func:ident, singular_func=$singular_func:ident) => {
+    (plural_func=$plural_func:ident, pages_func=$pages_func:ident, singular_func=$singular_func:ident) => {
         #[cfg(test)]
         mod $plural_func {
             use rstest::*;
@@ -42,7 +42,7 @@ macro_rules! test_endpoint_plural_and_singular {
                 let error = client.$plural_func(&params).expect_err(&format!("{} did not fail", stringify!($plural_func)));
 
                 // Assert
-                if let ClientError::ResponseError { errors } = error {
+                if let ClientError::BadRequest { errors } = error {
                     assert_eq!(errors.errors.len(), 1);
                 } else {
                     panic!("wrong error type");
@@ -66,6 +66,26 @@ macro_rules! test_endpoint_plural_and_singular {
                 }
             }
 
+            #[rstest]
+            fn success_plural_pages(client: Client) {
+                // Arrange
+                let params = [("page[limit]", "2")];
+
+                // Act
+                let mut pages = client.$pages_func(&params).expect(&format!("failed to paginate {}", stringify!($plural_func)));
+                let first = pages.next().expect("no first page").expect("failed to get first page");
+                let page_info = pages.page_info().expect("no page info");
+                let second = pages.next().expect("no second page").expect("failed to get second page");
+
+                // Assert
+                assert_eq!(first.data.len(), 2);
+                assert!(second.data.len() <= 2);
+                assert_eq!(page_info.limit, Some(2));
+                assert_eq!(page_info.next_offset, Some(2));
+                assert!(page_info.total_pages.is_some());
+                assert_ne!(first.data[0].id, second.data[0].id);
+            }
+
             #[rstest]
             fn success_singular_model(client: Client) {
                 // Arrange
@@ -91,7 +111,7 @@ macro_rules! test_endpoint_plural_and_singular {
                 let error = client.$singular_func("foobar").expect_err("facility did not fail");
 
                 // Assert
-                if let ClientError::ResponseError { errors } = error {
+                if let ClientError::NotFound { errors } = error {
                     assert_eq!(errors.errors.len(), 1);
                 } else {
                     panic!("wrong error type");
@@ -101,10 +121,11 @@ macro_rules! test_endpoint_plural_and_singular {
     };
 }
 
-test_endpoint_plural_and_singular!(plural_func = alerts, singular_func = alert);
-test_endpoint_plural_and_singular!(plural_func = facilities, singular_func = facility);
-test_endpoint_plural_and_singular!(plural_func = lines, singular_func = line);
-test_endpoint_plural_and_singular!(plural_func = route_patterns, singular_func = route_pattern);
-test_endpoint_plural_and_singular!(plural_func = routes, singular_func = route);
-test_endpoint_plural_and_singular!(plural_func = stops, singular_func = stop);
-test_endpoint_plural_and_singular!(plural_func = vehicles, singular_func = vehicle);
+test_endpoint_plural_and_singular!(plural_func = alerts, pages_func = alerts_pages, singular_func = alert);
+test_endpoint_plural_and_singular!(plural_func = facilities, pages_func = facilities_pages, singular_func = facility);
+test_endpoint_plural_and_singular!(plural_func = lines, pages_func = lines_pages, singular_func = line);
+test_endpoint_plural_and_singular!(plural_func = live_facilities, pages_func = live_facilities_pages, singular_func = live_facility);
+test_endpoint_plural_and_singular!(plural_func = route_patterns, pages_func = route_patterns_pages, singular_func = route_pattern);
+test_endpoint_plural_and_singular!(plural_func = routes, pages_func = routes_pages, singular_func = route);
+test_endpoint_plural_and_singular!(plural_func = stops, pages_func = stops_pages, singular_func = stop);
+test_endpoint_plural_and_singular!(plural_func = vehicles, pages_func = vehicles_pages, singular_func = vehicle);
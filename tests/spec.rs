@@ -0,0 +1,263 @@
+//! Audit of the data models and allowed query parameters against the upstream swagger spec of the V3 API.
+//!
+//! The spec is read from an unmodified copy vendored at `tests/data/swagger.json`, so the audit runs offline.
+//! To vendor or update it, run `cargo test --test spec -- --ignored vendor_spec`.
+
+use mbta_rs::*;
+use rstest::*;
+use serde::{
+    de::{value, DeserializeOwned, Error as _, Visitor},
+    forward_to_deserialize_any, Deserializer,
+};
+use serde_json::Value;
+
+/// Where the upstream swagger spec is published.
+const SPEC_URL: &str = "https://api-v3.mbta.com/docs/swagger/swagger.json";
+
+/// Where an unmodified copy of the upstream swagger spec can be vendored.
+const VENDORED_SPEC: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/data/swagger.json");
+
+/// Deserializer that records the field names of the struct asked of it, then fails without reading anything.
+struct FieldCollector<'a>(&'a mut Vec<&'static str>);
+
+impl<'de, 'a> Deserializer<'de> for FieldCollector<'a> {
+    type Error = value::Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, Self::Error> {
+        Err(value::Error::custom("not a struct"))
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        fields: &'static [&'static str],
+        _visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.0.extend(fields);
+        Err(value::Error::custom("fields collected"))
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string bytes byte_buf option unit unit_struct
+        newtype_struct seq tuple tuple_struct map enum identifier ignored_any
+    }
+}
+
+/// Names of the JSON fields a struct declares.
+fn fields<T: DeserializeOwned>() -> Vec<&'static str> {
+    let mut fields = vec![];
+    let _ = T::deserialize(FieldCollector(&mut fields));
+    fields
+}
+
+/// Transport that answers every request with an empty page.
+#[derive(Debug)]
+struct EmptyTransport;
+
+impl Transport for EmptyTransport {
+    fn send(&self, _request: &HttpRequest) -> Result<HttpResponse, ClientError> {
+        Ok(HttpResponse {
+            status: 200,
+            headers: vec![],
+            body: "{\"data\": [], \"jsonapi\": {\"version\": \"1.0\"}}".into(),
+        })
+    }
+}
+
+#[fixture]
+fn spec() -> Value {
+    let spec = std::fs::read_to_string(VENDORED_SPEC)
+        .unwrap_or_else(|_| panic!("{} is missing, vendor it with `cargo test --test spec -- --ignored vendor_spec`", VENDORED_SPEC));
+    serde_json::from_str(&spec).expect("invalid vendored swagger spec")
+}
+
+/// Download the upstream spec and vendor it as is, only run when asked for with `--ignored`.
+#[test]
+#[ignore]
+fn vendor_spec() {
+    let spec = ureq::get(SPEC_URL)
+        .call()
+        .expect("failed to download swagger spec")
+        .into_string()
+        .expect("failed to read swagger spec");
+    let version = serde_json::from_str::<Value>(&spec).expect("invalid swagger spec")["info"]["version"].clone();
+
+    std::fs::create_dir_all(std::path::Path::new(VENDORED_SPEC).parent().expect("no parent directory"))
+        .expect("failed to create tests/data");
+    std::fs::write(VENDORED_SPEC, spec).expect("failed to vendor swagger spec");
+
+    println!("vendored swagger spec version {} on {}", version, chrono::Utc::now().date_naive());
+}
+
+/// Names of the properties of an object in the spec.
+fn property_names(object: &Value) -> Vec<String> {
+    object["properties"]
+        .as_object()
+        .into_iter()
+        .flat_map(|properties| properties.keys().cloned())
+        .collect()
+}
+
+#[rstest]
+#[case::alert("AlertResource", fields::<AlertAttributes>())]
+#[case::facility("FacilityResource", fields::<FacilityAttributes>())]
+#[case::line("LineResource", fields::<LineAttributes>())]
+#[case::live_facility("LiveFacilityResource", fields::<LiveFacilityAttributes>())]
+#[case::prediction("PredictionResource", fields::<PredictionAttributes>())]
+#[case::route("RouteResource", fields::<RouteAttributes>())]
+#[case::route_pattern("RoutePatternResource", fields::<RoutePatternAttributes>())]
+#[case::schedule("ScheduleResource", fields::<ScheduleAttributes>())]
+#[case::service("ServiceResource", fields::<ServiceAttributes>())]
+#[case::shape("ShapeResource", fields::<ShapeAttributes>())]
+#[case::status("StatusResource", fields::<StatusAttributes>())]
+#[case::stop("StopResource", fields::<StopAttributes>())]
+#[case::trip("TripResource", fields::<TripAttributes>())]
+#[case::vehicle("VehicleResource", fields::<VehicleAttributes>())]
+#[ignore = "tests/data/swagger.json is not vendored yet, see CONTRIBUTE.md"]
+fn test_model_attributes(spec: Value, #[case] definition: &str, #[case] attributes: Vec<&str>) {
+    // Arrange
+    let resource = &spec["definitions"][definition];
+
+    // Act
+    let missing: Vec<String> = property_names(&resource["properties"]["attributes"])
+        .into_iter()
+        .filter(|name| !attributes.contains(&name.as_str()))
+        .collect();
+
+    // Assert
+    assert!(resource.is_object(), "{} is missing from the spec", definition);
+    assert!(missing.is_empty(), "{} attributes missing from the model: {:?}", definition, missing);
+}
+
+#[rstest]
+#[case::alert("AlertResource", Alert::RELATIONSHIPS)]
+#[case::facility("FacilityResource", Facility::RELATIONSHIPS)]
+#[case::line("LineResource", Line::RELATIONSHIPS)]
+#[case::live_facility("LiveFacilityResource", LiveFacility::RELATIONSHIPS)]
+#[case::prediction("PredictionResource", Prediction::RELATIONSHIPS)]
+#[case::route("RouteResource", Route::RELATIONSHIPS)]
+#[case::route_pattern("RoutePatternResource", RoutePattern::RELATIONSHIPS)]
+#[case::schedule("ScheduleResource", Schedule::RELATIONSHIPS)]
+#[case::service("ServiceResource", Service::RELATIONSHIPS)]
+#[case::shape("ShapeResource", Shape::RELATIONSHIPS)]
+#[case::status("StatusResource", Status::RELATIONSHIPS)]
+#[case::stop("StopResource", Stop::RELATIONSHIPS)]
+#[case::trip("TripResource", Trip::RELATIONSHIPS)]
+#[case::vehicle("VehicleResource", Vehicle::RELATIONSHIPS)]
+#[ignore = "tests/data/swagger.json is not vendored yet, see CONTRIBUTE.md"]
+fn test_model_relationships(spec: Value, #[case] definition: &str, #[case] relationships: &[&str]) {
+    // Arrange
+    let resource = &spec["definitions"][definition];
+
+    // Act
+    let missing: Vec<String> = property_names(&resource["properties"]["relationships"])
+        .into_iter()
+        .filter(|name| !relationships.contains(&name.as_str()))
+        .collect();
+
+    // Assert
+    assert!(resource.is_object(), "{} is missing from the spec", definition);
+    assert!(missing.is_empty(), "{} relationships missing from the model: {:?}", definition, missing);
+}
+
+/// Call the endpoint function for a path of the spec, ignoring the data it gets back,
+/// or [None] if the client has no function for the path.
+fn call(client: &Client, path: &str, params: &[(String, String)]) -> Option<Result<(), ClientError>> {
+    let result = match path {
+        "/alerts" => client.alerts(params).map(|_| ()),
+        "/facilities" => client.facilities(params).map(|_| ()),
+        "/lines" => client.lines(params).map(|_| ()),
+        "/live_facilities" => client.live_facilities(params).map(|_| ()),
+        "/predictions" => client.predictions(params).map(|_| ()),
+        "/routes" => client.routes(params).map(|_| ()),
+        "/route_patterns" => client.route_patterns(params).map(|_| ()),
+        "/schedules" => client.schedules(params).map(|_| ()),
+        "/services" => client.services(params).map(|_| ()),
+        "/shapes" => client.shapes(params).map(|_| ()),
+        "/stops" => client.stops(params).map(|_| ()),
+        "/trips" => client.trips(params).map(|_| ()),
+        "/vehicles" => client.vehicles(params).map(|_| ()),
+        "/alerts/{id}" => client.alert_with_params("1", params).map(|_| ()),
+        "/facilities/{id}" => client.facility_with_params("1", params).map(|_| ()),
+        "/lines/{id}" => client.line_with_params("1", params).map(|_| ()),
+        "/live_facilities/{id}" => client.live_facility_with_params("1", params).map(|_| ()),
+        "/routes/{id}" => client.route_with_params("1", params).map(|_| ()),
+        "/route_patterns/{id}" => client.route_pattern_with_params("1", params).map(|_| ()),
+        "/services/{id}" => client.service_with_params("1", params).map(|_| ()),
+        "/shapes/{id}" => client.shape_with_params("1", params).map(|_| ()),
+        "/stops/{id}" => client.stop_with_params("1", params).map(|_| ()),
+        "/trips/{id}" => client.trip_with_params("1", params).map(|_| ()),
+        "/vehicles/{id}" => client.vehicle_with_params("1", params).map(|_| ()),
+        "/status" => client.fetch_one::<Status, _, _>("", params).map(|_| ()),
+        _ => return None,
+    };
+    Some(result)
+}
+
+#[rstest]
+#[ignore = "tests/data/swagger.json is not vendored yet, see CONTRIBUTE.md"]
+fn test_paths_covered(spec: Value) {
+    // Arrange
+    let client = Client::without_key().with_transport(EmptyTransport);
+    let paths: Vec<&String> = spec["paths"].as_object().expect("spec has no paths").keys().collect();
+
+    // Act
+    let uncovered: Vec<&String> = paths.into_iter().filter(|path| call(&client, path, &[]).is_none()).collect();
+
+    // Assert
+    assert!(uncovered.is_empty(), "paths without an endpoint function: {:?}", uncovered);
+}
+
+#[rstest]
+#[case::alerts("/alerts")]
+#[case::facilities("/facilities")]
+#[case::lines("/lines")]
+#[case::live_facilities("/live_facilities")]
+#[case::predictions("/predictions")]
+#[case::routes("/routes")]
+#[case::route_patterns("/route_patterns")]
+#[case::schedules("/schedules")]
+#[case::services("/services")]
+#[case::shapes("/shapes")]
+#[case::stops("/stops")]
+#[case::trips("/trips")]
+#[case::vehicles("/vehicles")]
+#[case::alert("/alerts/{id}")]
+#[case::facility("/facilities/{id}")]
+#[case::line("/lines/{id}")]
+#[case::live_facility("/live_facilities/{id}")]
+#[case::route("/routes/{id}")]
+#[case::route_pattern("/route_patterns/{id}")]
+#[case::service("/services/{id}")]
+#[case::shape("/shapes/{id}")]
+#[case::stop("/stops/{id}")]
+#[case::trip("/trips/{id}")]
+#[case::vehicle("/vehicles/{id}")]
+#[case::status("/status")]
+#[ignore = "tests/data/swagger.json is not vendored yet, see CONTRIBUTE.md"]
+fn test_allowed_query_params(spec: Value, #[case] path: &str) {
+    // Arrange
+    let client = Client::without_key().with_transport(EmptyTransport);
+    let operation = &spec["paths"][path]["get"];
+    let parameters = operation["parameters"].as_array().cloned().unwrap_or_default();
+
+    // Act
+    let rejected: Vec<String> = parameters
+        .iter()
+        .filter(|parameter| parameter["in"] == "query")
+        .filter_map(|parameter| parameter["name"].as_str())
+        // Sparse fieldsets leave out attributes that the models require, so they aren't supported.
+        .filter(|name| !name.starts_with("fields["))
+        .filter(|name| {
+            matches!(
+                call(&client, path, &[(name.to_string(), "1".to_string())]),
+                Some(Err(ClientError::InvalidQueryParam { .. }))
+            )
+        })
+        .map(String::from)
+        .collect();
+
+    // Assert
+    assert!(operation.is_object(), "{} is missing from the spec", path);
+    assert!(rejected.is_empty(), "{} query parameters rejected by the client: {:?}", path, rejected);
+}
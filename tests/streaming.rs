@@ -0,0 +1,171 @@
+//! Offline testing of event streams against a local server-sent events stand-in.
+
+mod common;
+
+use std::time::Duration;
+
+use common::*;
+use mbta_rs::*;
+use rstest::*;
+
+fn vehicle(id: &str, label: &str) -> String {
+    format!(
+        "{{\"type\": \"vehicle\", \"id\": \"{}\", \"attributes\": {{\"updated_at\": \"2022-04-02T15:14:06-04:00\", \"speed\": null, \"occupancy_status\": null, \"longitude\": -71.0, \"latitude\": 42.3, \"label\": \"{}\", \"direction_id\": 0, \"current_stop_sequence\": 1, \"current_status\": \"STOPPED_AT\", \"bearing\": 90}}, \"relationships\": {{\"route\": {{\"data\": {{\"type\": \"route\", \"id\": \"Red\"}}}}}}}}",
+        id, label
+    )
+}
+
+fn event_stream(body: &str) -> String {
+    http_response("200 OK", &[("content-type", "text/event-stream")], body)
+}
+
+#[rstest]
+fn test_stream_events() {
+    // Arrange
+    let body = format!(
+        "event: reset\ndata: [{}]\n\nevent: add\ndata: {}\n\n: keep-alive\n\nevent: update\ndata: {}\n\nevent: remove\ndata: {{\"type\": \"vehicle\", \"id\": \"y1\"}}\n\n",
+        vehicle("y1", "1"),
+        vehicle("y2", "2"),
+        vehicle("y2", "3")
+    );
+    let server = TestServer::new(vec![event_stream(&body)]);
+    let client = Client::with_url(&server.url);
+
+    // Act
+    let events: Vec<Event<Vehicle>> = client
+        .stream_vehicles(&[("filter[route]", "Red")])
+        .expect("failed to stream vehicles")
+        .take(5)
+        .collect::<Result<_, _>>()
+        .expect("failed to read events");
+
+    // Assert
+    let requests = server.requests();
+    assert_eq!(events.len(), 5);
+    assert!(matches!(&events[0], Event::Reset(vehicles) if vehicles.len() == 1 && vehicles[0].route_id().as_deref() == Some("Red")));
+    assert!(matches!(&events[1], Event::Add(vehicle) if vehicle.id == "y2"));
+    assert_eq!(events[2], Event::KeepAlive);
+    assert!(matches!(&events[3], Event::Update(vehicle) if vehicle.attributes.label == "3"));
+    assert!(matches!(&events[4], Event::Remove(removed) if removed.id == VehicleId::new("y1") && removed.relationship_type == "vehicle"));
+    assert!(requests[0].starts_with("GET /vehicles?filter%5Broute%5D=Red HTTP/1.1"));
+    assert!(requests[0].to_lowercase().contains("accept: text/event-stream"));
+}
+
+#[rstest]
+fn test_stream_generic_endpoint() {
+    // Arrange
+    let body = format!("event: add\ndata: {}\n\n", vehicle("y1", "1"));
+    let server = TestServer::new(vec![event_stream(&body)]);
+    let client = Client::with_url(&server.url);
+
+    // Act
+    let event = client
+        .stream::<Vehicles, _, _>(&[("filter[route]", "Red")])
+        .expect("failed to stream vehicles")
+        .next()
+        .expect("stream ended")
+        .expect("failed to read event");
+    let error = client.stream::<Vehicles, _, _>(&[("sort", "label")]).err();
+
+    // Assert
+    assert!(matches!(event, Event::Add(vehicle) if vehicle.id == "y1"));
+    assert!(matches!(error, Some(ClientError::InvalidQueryParam { .. })));
+    assert!(server.requests()[0].starts_with("GET /vehicles?filter%5Broute%5D=Red HTTP/1.1"));
+}
+
+#[rstest]
+fn test_stream_reconnects_from_last_event() {
+    // Arrange
+    let first = format!("id: 7\nevent: add\ndata: {}\n\n", vehicle("y1", "1"));
+    let second = format!("id: 8\nevent: update\ndata: {}\n\n", vehicle("y1", "2"));
+    let server = TestServer::new(vec![event_stream(&first), event_stream(&second)]);
+    let client = Client::with_url(&server.url);
+
+    // Act
+    let mut stream = client
+        .stream_vehicles::<String, String>(&[])
+        .expect("failed to stream vehicles")
+        .with_reconnect_delay(Duration::from_millis(10));
+    let first_event = stream.next();
+    let second_event = stream.next();
+
+    // Assert
+    let requests = server.requests();
+    assert!(matches!(first_event, Some(Ok(Event::Add(_)))));
+    assert!(matches!(second_event, Some(Ok(Event::Update(_)))));
+    assert_eq!(stream.last_event_id(), Some("8"));
+    assert_eq!(requests.len(), 2);
+    assert!(!requests[0].to_lowercase().contains("last-event-id"));
+    assert!(requests[1].to_lowercase().contains("last-event-id: 7"));
+}
+
+#[rstest]
+fn test_stream_gives_up_after_failed_reconnects() {
+    // Arrange
+    let server = TestServer::new(vec![event_stream(": connected\n\n"), event_stream("")]);
+    let client = Client::with_url(&server.url);
+
+    // Act
+    let events: Vec<Result<Event<Vehicle>, ClientError>> = client
+        .stream_vehicles::<String, String>(&[])
+        .expect("failed to stream vehicles")
+        .with_reconnect_delay(Duration::from_millis(10))
+        .with_max_reconnects(1)
+        .collect();
+
+    // Assert
+    assert_eq!(server.requests().len(), 2);
+    assert_eq!(events.len(), 2);
+    assert!(matches!(events[0], Ok(Event::KeepAlive)));
+    assert!(matches!(events[1], Err(ClientError::TransportError(_))));
+}
+
+#[rstest]
+fn test_stream_error_response() {
+    // Arrange
+    let server = TestServer::new(vec![http_response(
+        "400 Bad Request",
+        &[("content-type", "application/vnd.api+json")],
+        "{\"errors\": [{\"status\": \"400\", \"code\": \"bad_request\"}], \"jsonapi\": {\"version\": \"1.0\"}}",
+    )]);
+    let client = Client::with_url(&server.url);
+
+    // Act
+    let mut stream = client
+        .stream_alerts(&[("filter[route]", "Red")])
+        .expect("failed to stream alerts")
+        .with_max_reconnects(0);
+    let first = stream.next();
+    let second = stream.next();
+
+    // Assert
+    assert!(matches!(first, Some(Err(ClientError::BadRequest { .. }))));
+    assert!(second.is_none());
+}
+
+#[rstest]
+fn test_stream_invalid_query_param() {
+    // Arrange
+    let client = Client::without_key();
+
+    // Act
+    let error = client.stream_predictions(&[("page[limit]", "3")]).expect_err("stream did not fail");
+
+    // Assert
+    assert!(matches!(error, ClientError::InvalidQueryParam { .. }));
+}
+
+#[rstest]
+#[case::alerts(Alerts::ALLOWED_QUERY_PARAMS, Alerts::STREAM_QUERY_PARAMS)]
+#[case::predictions(Predictions::ALLOWED_QUERY_PARAMS, Predictions::STREAM_QUERY_PARAMS)]
+#[case::vehicles(Vehicles::ALLOWED_QUERY_PARAMS, Vehicles::STREAM_QUERY_PARAMS)]
+fn test_stream_allows_endpoint_filters(#[case] allowed: &[&str], #[case] streamed: &[&str]) {
+    // Arrange
+    let filters = allowed.iter().filter(|param| param.starts_with("filter["));
+
+    // Act
+    let missing: Vec<&&str> = filters.filter(|filter| !streamed.contains(filter)).collect();
+
+    // Assert
+    assert!(missing.is_empty(), "filters missing from the stream: {:?}", missing);
+}
@@ -0,0 +1,646 @@
+//! Offline testing of the client against an in-memory transport.
+
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use chrono::{TimeZone, Utc};
+
+use mbta_rs::*;
+use rstest::*;
+
+/// Extra response headers as pairings of names to values.
+type Headers = Vec<(&'static str, String)>;
+
+/// In-memory transport that answers requests from a list of canned responses and records every request it sends.
+#[derive(Debug, Clone, Default)]
+struct InMemoryTransport {
+    /// Canned responses, returned in order.
+    responses: Arc<Mutex<Vec<HttpResponse>>>,
+    /// Requests sent so far.
+    requests: Arc<Mutex<Vec<HttpRequest>>>,
+}
+
+impl InMemoryTransport {
+    fn new(responses: Vec<(u16, &str)>) -> Self {
+        Self::with_headers(responses.into_iter().map(|(status, body)| (status, vec![], body)).collect())
+    }
+
+    fn with_headers(responses: Vec<(u16, Headers, &str)>) -> Self {
+        let responses = responses
+            .into_iter()
+            .rev()
+            .map(|(status, headers, body)| HttpResponse {
+                status,
+                headers: [("content-type".to_string(), "application/vnd.api+json".to_string())]
+                    .into_iter()
+                    .chain(headers.into_iter().map(|(k, v)| (k.to_string(), v)))
+                    .collect(),
+                body: body.into(),
+            })
+            .collect();
+        Self {
+            responses: Arc::new(Mutex::new(responses)),
+            requests: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+
+    fn requests(&self) -> Vec<HttpRequest> {
+        self.requests.lock().expect("poisoned lock").clone()
+    }
+}
+
+impl Transport for InMemoryTransport {
+    fn send(&self, request: &HttpRequest) -> Result<HttpResponse, ClientError> {
+        self.requests.lock().expect("poisoned lock").push(request.clone());
+        Ok(self.responses.lock().expect("poisoned lock").pop().expect("no more canned responses"))
+    }
+}
+
+fn shapes_page(ids: &[&str], next: Option<u64>) -> String {
+    let data: Vec<String> = ids
+        .iter()
+        .map(|id| format!("{{\"type\": \"shape\", \"id\": \"{}\", \"attributes\": {{\"polyline\": \"abc\"}}}}", id))
+        .collect();
+    let next = match next {
+        Some(offset) => format!("\"https://api-v3.mbta.com/shapes?page%5Blimit%5D=2&page%5Boffset%5D={}\"", offset),
+        None => "null".into(),
+    };
+    format!(
+        "{{\"data\": [{}], \"jsonapi\": {{\"version\": \"1.0\"}}, \"links\": {{\"first\": \"https://api-v3.mbta.com/shapes?page%5Blimit%5D=2&page%5Boffset%5D=0\", \"next\": {}, \"last\": \"https://api-v3.mbta.com/shapes?page%5Blimit%5D=2&page%5Boffset%5D=2\"}}}}",
+        data.join(", "),
+        next
+    )
+}
+
+#[rstest]
+fn test_request_shape() {
+    // Arrange
+    let transport = InMemoryTransport::new(vec![(200, &shapes_page(&["a"], None))]);
+    let client = Client::with_key("test key").with_transport(transport.clone());
+
+    // Act
+    let shapes = client.shapes(&[("filter[route]", "Red")]).expect("failed to get shapes");
+
+    // Assert
+    let requests = transport.requests();
+    assert_eq!(shapes.data.len(), 1);
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0].method, Method::Get);
+    assert_eq!(requests[0].url, "https://api-v3.mbta.com/shapes");
+    assert_eq!(requests[0].header("X-API-KEY"), Some("test key"));
+    assert_eq!(requests[0].query, vec![("filter[route]".to_string(), "Red".to_string())]);
+}
+
+#[rstest]
+fn test_single_request_shape() {
+    // Arrange
+    let transport = InMemoryTransport::new(vec![(
+        200,
+        "{\"data\": {\"type\": \"shape\", \"id\": \"a\", \"attributes\": {\"polyline\": \"abc\"}}, \"jsonapi\": {\"version\": \"1.0\"}}",
+    )]);
+    let client = Client::with_url("http://localhost").with_transport(transport.clone());
+
+    // Act
+    let shape = client.shape_with_params("a", &[("include", "route")]).expect("failed to get shape");
+
+    // Assert
+    let requests = transport.requests();
+    assert_eq!(shape.data.id, "a");
+    assert_eq!(requests[0].url, "http://localhost/shapes/a");
+    assert_eq!(requests[0].header("x-api-key"), None);
+    assert_eq!(requests[0].query, vec![("include".to_string(), "route".to_string())]);
+}
+
+#[rstest]
+#[case::bad_request(
+    400,
+    "{\"errors\": [{\"status\": \"400\", \"code\": \"bad_request\"}], \"jsonapi\": {\"version\": \"1.0\"}}"
+)]
+#[case::forbidden(
+    403,
+    "{\"errors\": [{\"status\": \"403\", \"code\": \"forbidden\"}], \"jsonapi\": {\"version\": \"1.0\"}}"
+)]
+#[case::not_found(
+    404,
+    "{\"errors\": [{\"status\": \"404\", \"code\": \"not_found\"}], \"jsonapi\": {\"version\": \"1.0\"}}"
+)]
+#[case::conflict(
+    409,
+    "{\"errors\": [{\"status\": \"409\", \"code\": \"conflict\"}], \"jsonapi\": {\"version\": \"1.0\"}}"
+)]
+#[case::unexpected(404, "<html>Not Found</html>")]
+#[case::server_error(502, "<html>Bad Gateway</html>")]
+fn test_error_response(#[case] status: u16, #[case] body: &str) {
+    // Arrange
+    let transport = InMemoryTransport::new(vec![(status, body)]);
+    let client = Client::without_key().with_transport(transport);
+
+    // Act
+    let error = client.shapes(&[("sort", "foobar")]).expect_err("shapes did not fail");
+
+    // Assert
+    assert_eq!(error.status(), Some(status));
+    assert_eq!(error.is_not_found(), status == 404);
+    assert_eq!(error.is_retryable(), status >= 500);
+    match (status, error) {
+        (400, ClientError::BadRequest { errors })
+        | (403, ClientError::Forbidden { errors })
+        | (404, ClientError::NotFound { errors })
+        | (409, ClientError::ResponseError { errors, .. }) => assert_eq!(errors.errors.len(), 1),
+        (404, ClientError::UnexpectedResponse { body, .. }) | (502, ClientError::ServerError { body, .. }) => {
+            assert!(body.starts_with("<html>"))
+        }
+        (_, error) => panic!("wrong error type: {:?}", error),
+    }
+}
+
+#[rstest]
+fn test_status() {
+    // Arrange
+    let transport = InMemoryTransport::new(vec![(
+        200,
+        "{\"data\": {\"type\": \"status\", \"id\": \"status\", \"attributes\": {\"feed\": {\"version\": \"Spring 2022\", \"start_date\": \"2022-05-06\", \"end_date\": \"2022-08-26\"}}}, \"jsonapi\": {\"version\": \"1.0\"}}",
+    )]);
+    let client = Client::with_url("http://localhost").with_transport(transport.clone());
+
+    // Act
+    let status = client.status().expect("failed to get status");
+
+    // Assert
+    assert_eq!(status.data.attributes.feed.version, "Spring 2022");
+    assert_eq!(status.data.attributes.feed.end_date.format(DATE_FORMAT).to_string(), "2022-08-26");
+    assert_eq!(transport.requests()[0].url, "http://localhost/status");
+}
+
+#[rstest]
+fn test_get_raw() {
+    // Arrange
+    let transport = InMemoryTransport::new(vec![(200, &shapes_page(&["a"], None))]);
+    let client = Client::with_url("http://localhost").with_transport(transport.clone());
+
+    // Act
+    let body = client.get_raw("/shapes", &[("filter[unmodeled]", "1")]).expect("failed to get shapes");
+
+    // Assert
+    let requests = transport.requests();
+    assert_eq!(body["data"][0]["id"], "a");
+    assert_eq!(requests[0].url, "http://localhost/shapes");
+    assert_eq!(requests[0].query, vec![("filter[unmodeled]".to_string(), "1".to_string())]);
+}
+
+#[rstest]
+#[case::success(200, Some("a"))]
+#[case::not_found(404, None)]
+fn test_get_as(#[case] status: u16, #[case] expected_id: Option<&str>) {
+    // Arrange
+    let body = match status {
+        200 => {
+            "{\"data\": {\"type\": \"shape\", \"id\": \"a\", \"attributes\": {\"polyline\": \"abc\"}}, \"jsonapi\": {\"version\": \"1.0\"}}"
+        }
+        _ => "{\"errors\": [{\"status\": \"404\", \"code\": \"not_found\"}], \"jsonapi\": {\"version\": \"1.0\"}}",
+    };
+    let client = Client::with_url("http://localhost").with_transport(InMemoryTransport::new(vec![(status, body)]));
+
+    // Act
+    let actual = client.get_as::<Response<Shape>, String, String>("shapes/a", &[]);
+
+    // Assert
+    match expected_id {
+        Some(id) => assert_eq!(actual.expect("failed to get shape").data.id, id),
+        None => assert!(actual.expect_err("shape did not fail").is_not_found()),
+    }
+}
+
+/// Endpoint the crate doesn't know about, added the way a downstream crate would.
+#[derive(Debug)]
+struct Polylines;
+
+impl Endpoint for Polylines {
+    type Model = Vec<Resource<ShapeAttributes>>;
+    const PATH: &'static str = "polylines";
+    const ALLOWED_QUERY_PARAMS: &'static [&'static str] = &["page[limit]", "page[offset]", "filter[route]"];
+}
+
+impl MultipleEndpoint for Polylines {}
+
+#[rstest]
+fn test_fetch_custom_endpoint() {
+    // Arrange
+    let transport = InMemoryTransport::new(vec![(200, &shapes_page(&["a", "b"], None))]);
+    let client = Client::with_url("http://localhost").with_transport(transport.clone());
+
+    // Act
+    let polylines = client.fetch::<Polylines, _, _>(&[("filter[route]", "Red")]).expect("failed to get polylines");
+    let error = client.fetch::<Polylines, _, _>(&[("sort", "id")]).expect_err("polylines did not fail");
+
+    // Assert
+    assert_eq!(polylines.data.len(), 2);
+    assert_eq!(transport.requests()[0].url, "http://localhost/polylines");
+    assert!(matches!(error, ClientError::InvalidQueryParam { .. }));
+}
+
+#[rstest]
+fn test_fetch_one() {
+    // Arrange
+    let transport = InMemoryTransport::new(vec![(
+        200,
+        "{\"data\": {\"type\": \"shape\", \"id\": \"a\", \"attributes\": {\"polyline\": \"abc\"}}, \"jsonapi\": {\"version\": \"1.0\"}}",
+    )]);
+    let client = Client::with_url("http://localhost").with_transport(transport.clone());
+
+    // Act
+    let shape = client.fetch_one::<Shape, _, _>("a", &[("include", "route")]).expect("failed to get shape");
+
+    // Assert
+    assert_eq!(shape.data.id, "a");
+    assert_eq!(transport.requests()[0].url, "http://localhost/shapes/a");
+}
+
+#[rstest]
+fn test_fetch_one_status() {
+    // Arrange
+    let transport = InMemoryTransport::new(vec![(
+        200,
+        "{\"data\": {\"type\": \"status\", \"id\": \"status\", \"attributes\": {\"feed\": {\"version\": \"Spring 2022\", \"start_date\": \"2022-05-06\", \"end_date\": \"2022-08-26\"}}}, \"jsonapi\": {\"version\": \"1.0\"}}",
+    )]);
+    let client = Client::with_url("http://localhost").with_transport(transport.clone());
+
+    // Act
+    let status = client.fetch_one::<Status, String, String>("", &[]).expect("failed to get status");
+    let error = client.fetch_one::<Status, _, _>("", &[("include", "feed")]).expect_err("status did not fail");
+
+    // Assert
+    assert_eq!(status.data.attributes.feed.version, "Spring 2022");
+    assert_eq!(transport.requests().len(), 1);
+    assert_eq!(transport.requests()[0].url, "http://localhost/status");
+    assert!(matches!(error, ClientError::InvalidQueryParam { .. }));
+}
+
+#[rstest]
+fn test_deserialization_error() {
+    // Arrange
+    let body = "{\"data\": [{\"type\": \"shape\", \"id\": \"a\", \"attributes\": {\"polyline\": 1}}], \"jsonapi\": {\"version\": \"1.0\"}}";
+    let transport = InMemoryTransport::new(vec![(200, body)]);
+    let client = Client::without_key().with_transport(transport);
+
+    // Act
+    let error = client.shapes::<String, String>(&[]).expect_err("shapes did not fail");
+
+    // Assert
+    match error {
+        ClientError::DeserializationError(details) => {
+            assert_eq!(details.url, "https://api-v3.mbta.com/shapes");
+            assert_eq!(details.path, "data[0].attributes.polyline");
+            assert_eq!(details.body, body);
+        }
+        error => panic!("wrong error type: {:?}", error),
+    }
+}
+
+#[rstest]
+fn test_query_builder_request() {
+    // Arrange
+    let transport = InMemoryTransport::new(vec![(200, &shapes_page(&[], None))]);
+    let client = Client::without_key().with_transport(transport.clone());
+    let query = ShapesQuery::new().route(["Red", "Blue"]).page_limit(2).build().expect("invalid query");
+
+    // Act
+    client.shapes(&query).expect("failed to get shapes");
+
+    // Assert
+    assert_eq!(
+        transport.requests()[0].query,
+        vec![
+            ("filter[route]".to_string(), "Red,Blue".to_string()),
+            ("page[limit]".to_string(), "2".to_string())
+        ]
+    );
+}
+
+#[rstest]
+fn test_pages_follow_next_links() {
+    // Arrange
+    let transport = InMemoryTransport::new(vec![(200, &shapes_page(&["a", "b"], Some(2))), (200, &shapes_page(&["c"], None))]);
+    let client = Client::without_key().with_transport(transport.clone());
+
+    // Act
+    let pages = client.shapes_pages(&[("page[limit]", "2")]).expect("failed to paginate shapes");
+    let items: Vec<ShapeId> = pages.items().map(|item| item.expect("failed to get shape").id).collect();
+
+    // Assert
+    let requests = transport.requests();
+    assert_eq!(items, vec!["a", "b", "c"]);
+    assert_eq!(requests.len(), 2);
+    assert_eq!(requests[0].query, vec![("page[limit]".to_string(), "2".to_string())]);
+    assert_eq!(
+        requests[1].query,
+        vec![
+            ("page[limit]".to_string(), "2".to_string()),
+            ("page[offset]".to_string(), "2".to_string())
+        ]
+    );
+}
+
+#[rstest]
+fn test_pages_stop_after_error() {
+    // Arrange
+    let transport = InMemoryTransport::new(vec![(200, &shapes_page(&["a", "b"], Some(2))), (503, "unavailable")]);
+    let client = Client::without_key().with_transport(transport.clone());
+
+    // Act
+    let mut pages = client.shapes_pages(&[("page[limit]", "2")]).expect("failed to paginate shapes");
+    let first = pages.next();
+    let second = pages.next();
+    let third = pages.next();
+
+    // Assert
+    assert!(matches!(first, Some(Ok(_))));
+    assert_eq!(pages.page_info().map(|p| p.total_pages), Some(Some(2)));
+    assert!(matches!(second, Some(Err(_))));
+    assert!(third.is_none());
+    assert_eq!(transport.requests().len(), 2);
+}
+
+fn rate_limit_headers(remaining: u64, reset: i64) -> Headers {
+    vec![
+        ("x-ratelimit-limit", "20".into()),
+        ("x-ratelimit-remaining", remaining.to_string()),
+        ("x-ratelimit-reset", reset.to_string()),
+    ]
+}
+
+#[rstest]
+fn test_rate_limit_state() {
+    // Arrange
+    let transport = InMemoryTransport::with_headers(vec![(200, rate_limit_headers(19, 1_650_000_000), &shapes_page(&["a"], None))]);
+    let client = Client::without_key().with_transport(transport);
+    let expected = RateLimit {
+        limit: 20,
+        remaining: 19,
+        reset_at: Utc.timestamp_opt(1_650_000_000, 0).unwrap(),
+    };
+
+    // Act
+    let before = client.rate_limit();
+    let shapes = client.shapes(&[("filter[route]", "Red")]).expect("failed to get shapes");
+
+    // Assert
+    assert_eq!(before, None);
+    assert_eq!(shapes.meta.rate_limit, Some(expected));
+    assert_eq!(client.rate_limit(), Some(expected));
+    assert_eq!(client.clone().rate_limit(), Some(expected));
+}
+
+#[rstest]
+fn test_rate_limited_error() {
+    // Arrange
+    let transport = InMemoryTransport::with_headers(vec![(429, rate_limit_headers(0, 1_650_000_000), "")]);
+    let client = Client::without_key().with_transport(transport.clone());
+
+    // Act
+    let error = client.shapes(&[("filter[route]", "Red")]).expect_err("shapes did not fail");
+
+    // Assert
+    assert!(matches!(error, ClientError::RateLimited { reset_at } if reset_at == Utc.timestamp_opt(1_650_000_000, 0).single()));
+    assert_eq!(transport.requests().len(), 1);
+}
+
+#[rstest]
+fn test_rate_limited_retry() {
+    // Arrange
+    let reset = Utc::now().timestamp();
+    let transport = InMemoryTransport::with_headers(vec![
+        (429, rate_limit_headers(0, reset), ""),
+        (200, rate_limit_headers(19, reset + 60), &shapes_page(&["a"], None)),
+    ]);
+    let client = Client::without_key()
+        .with_transport(transport.clone())
+        .with_rate_limit_policy(RateLimitPolicy::WaitForReset {
+            max_wait: DEFAULT_MAX_RATE_LIMIT_WAIT,
+        });
+
+    // Act
+    let shapes = client.shapes(&[("filter[route]", "Red")]).expect("failed to get shapes");
+
+    // Assert
+    assert_eq!(shapes.data.len(), 1);
+    assert_eq!(transport.requests().len(), 2);
+    assert_eq!(client.rate_limit().map(|r| r.remaining), Some(19));
+}
+
+#[rstest]
+fn test_rate_limited_without_reset() {
+    // Arrange
+    let transport = InMemoryTransport::with_headers(vec![
+        (429, vec![], ""),
+        (200, rate_limit_headers(19, Utc::now().timestamp() + 60), &shapes_page(&["a"], None)),
+    ]);
+    let client = Client::without_key()
+        .with_transport(transport.clone())
+        .with_rate_limit_policy(RateLimitPolicy::WaitForReset {
+            max_wait: DEFAULT_MAX_RATE_LIMIT_WAIT,
+        });
+
+    // Act
+    let error = client.shapes(&[("filter[route]", "Red")]).expect_err("shapes did not fail");
+
+    // Assert
+    assert!(matches!(error, ClientError::RateLimited { reset_at: None }));
+    assert_eq!(transport.requests().len(), 1);
+}
+
+#[rstest]
+fn test_rate_limited_reset_too_far() {
+    // Arrange
+    let reset = Utc::now().timestamp() + 3_600;
+    let transport = InMemoryTransport::with_headers(vec![(429, rate_limit_headers(0, reset), "")]);
+    let client = Client::without_key()
+        .with_transport(transport.clone())
+        .with_rate_limit_policy(RateLimitPolicy::WaitForReset {
+            max_wait: Duration::from_secs(5),
+        });
+
+    // Act
+    let first = client.shapes(&[("filter[route]", "Red")]).expect_err("waited for rate limit");
+    let second = client.shapes(&[("filter[route]", "Red")]).expect_err("waited for rate limit");
+
+    // Assert
+    for error in [first, second] {
+        assert!(matches!(error, ClientError::RateLimited { reset_at } if reset_at == Utc.timestamp_opt(reset, 0).single()));
+    }
+    assert_eq!(transport.requests().len(), 1);
+}
+
+#[rstest]
+fn test_conditional_requests() {
+    // Arrange
+    let last_modified = "Sat, 02 Apr 2022 19:14:06 GMT";
+    let transport = InMemoryTransport::with_headers(vec![
+        (200, vec![("last-modified", last_modified.into())], &shapes_page(&["a"], None)),
+        (304, vec![("last-modified", last_modified.into())], ""),
+    ]);
+    let client = Client::without_key().with_transport(transport.clone()).with_conditional_requests(true);
+
+    // Act
+    let first = client.shapes(&[("filter[route]", "Red"), ("page[limit]", "2")]).expect("failed to get shapes");
+    let second = client.shapes(&[("page[limit]", "2"), ("filter[route]", "Red")]).expect("failed to get shapes");
+
+    // Assert
+    let requests = transport.requests();
+    assert!(!first.meta.not_modified);
+    assert!(second.meta.not_modified);
+    assert_eq!(first.data, second.data);
+    assert_eq!(requests[0].header("if-modified-since"), None);
+    assert_eq!(requests[1].header("if-modified-since"), Some(last_modified));
+}
+
+#[rstest]
+fn test_conditional_requests_disabled() {
+    // Arrange
+    let transport = InMemoryTransport::with_headers(vec![
+        (200, vec![("last-modified", "Sat, 02 Apr 2022 19:14:06 GMT".into())], &shapes_page(&["a"], None)),
+        (200, vec![], &shapes_page(&["b"], None)),
+    ]);
+    let client = Client::without_key().with_transport(transport.clone());
+
+    // Act
+    client.shapes::<String, String>(&[]).expect("failed to get shapes");
+    let second = client.shapes::<String, String>(&[]).expect("failed to get shapes");
+
+    // Assert
+    assert_eq!(transport.requests()[1].header("if-modified-since"), None);
+    assert_eq!(second.data[0].id, "b");
+}
+
+#[rstest]
+#[case::cached("shapes", 1, "a")]
+#[case::uncached("vehicles", 2, "b")]
+fn test_response_cache(#[case] endpoint: &str, #[case] expected_requests: usize, #[case] expected_id: &str) {
+    // Arrange
+    let transport = InMemoryTransport::new(vec![(200, &shapes_page(&["a"], None)), (200, &shapes_page(&["b"], None))]);
+    let policy = CachePolicy::new().with_ttl(endpoint, Duration::from_secs(60));
+    let client = Client::without_key().with_transport(transport.clone()).with_cache(MemoryCache::new(10), policy);
+
+    // Act
+    let first = client.shapes(&[("filter[route]", "Red"), ("page[limit]", "2")]).expect("failed to get shapes");
+    let second = client.shapes(&[("page[limit]", "2"), ("filter[route]", "Red")]).expect("failed to get shapes");
+
+    // Assert
+    assert!(!first.meta.cached);
+    assert_eq!(second.meta.cached, expected_requests == 1);
+    assert_eq!(transport.requests().len(), expected_requests);
+    assert_eq!(second.data[0].id, expected_id);
+}
+
+#[rstest]
+fn test_response_cache_expired() {
+    // Arrange
+    let transport = InMemoryTransport::new(vec![(200, &shapes_page(&["a"], None)), (200, &shapes_page(&["b"], None))]);
+    let client = Client::without_key()
+        .with_transport(transport.clone())
+        .with_cache(MemoryCache::new(10), CachePolicy::static_data(Duration::ZERO));
+
+    // Act
+    client.shapes::<String, String>(&[]).expect("failed to get shapes");
+    let second = client.shapes::<String, String>(&[]).expect("failed to get shapes");
+
+    // Assert
+    assert!(!second.meta.cached);
+    assert_eq!(second.data[0].id, "b");
+}
+
+#[rstest]
+fn test_response_meta() {
+    // Arrange
+    let last_modified = "Sat, 02 Apr 2022 19:14:06 GMT";
+    let transport = InMemoryTransport::with_headers(vec![(200, vec![("Last-Modified", last_modified.into())], &shapes_page(&["a"], None))]);
+    let client = Client::builder()
+        .api_key("test key")
+        .key_location(KeyLocation::QueryParam)
+        .build()
+        .expect("failed to build client")
+        .with_transport(transport);
+
+    // Act
+    let shapes = client.shapes(&[("filter[route]", "Red")]).expect("failed to get shapes");
+
+    // Assert
+    assert_eq!(shapes.meta.status, 200);
+    assert_eq!(shapes.meta.url, "https://api-v3.mbta.com/shapes");
+    assert_eq!(shapes.meta.query, vec![("filter[route]".to_string(), "Red".to_string())]);
+    assert_eq!(shapes.meta.last_modified(), Some(last_modified));
+    assert_eq!(shapes.meta.header("content-type"), Some("application/vnd.api+json"));
+    assert!(!shapes.meta.not_modified && !shapes.meta.cached);
+}
+
+#[rstest]
+#[case::lenient(true)]
+#[case::strict(false)]
+fn test_lenient_decoding(#[case] lenient: bool) {
+    // Arrange
+    let body = "{\"data\": [{\"type\": \"shape\", \"id\": \"a\", \"attributes\": {\"polyline\": \"abc\"}}, {\"type\": \"shape\", \"id\": \"b\", \"attributes\": {}}], \"jsonapi\": {\"version\": \"1.0\"}}";
+    let transport = InMemoryTransport::new(vec![(200, body)]);
+    let client = Client::without_key().with_transport(transport).with_lenient_decoding(lenient);
+
+    // Act
+    let result = client.shapes::<String, String>(&[]);
+
+    // Assert
+    match result {
+        Ok(shapes) => {
+            assert!(lenient);
+            assert_eq!(shapes.data.len(), 1);
+            assert_eq!(shapes.meta.item_errors.len(), 1);
+            assert_eq!(shapes.meta.item_errors[0].id.as_deref(), Some("b"));
+            assert_eq!(shapes.meta.item_errors[0].path, "data[1].attributes");
+        }
+        Err(error) => {
+            assert!(!lenient);
+            assert!(matches!(error, ClientError::DeserializationError(_)));
+        }
+    }
+}
+
+#[rstest]
+fn test_schema_drift_detection() {
+    // Arrange
+    let body = "{\"data\": [{\"type\": \"vehicle\", \"id\": \"y1234\", \"attributes\": {\"bearing\": 90, \"carriages\": [{\"label\": \"1\", \"carriage_sequence\": 1}], \"current_status\": \"STOPPED_AT\", \"current_stop_sequence\": 1, \"direction_id\": 0, \"label\": \"1234\", \"latitude\": 42.0, \"longitude\": -71.0, \"occupancy_status\": null, \"speed\": null, \"updated_at\": \"2022-04-02T15:14:06-04:00\"}, \"relationships\": {\"route\": {\"data\": {\"type\": \"route\", \"id\": \"1\"}}, \"occupancy\": {\"data\": null}}}], \"jsonapi\": {\"version\": \"1.0\"}}";
+    let transport = InMemoryTransport::new(vec![(200, body), (200, body)]);
+    let client = Client::without_key().with_transport(transport).with_schema_drift_detection(true);
+    let disabled = client.clone().with_schema_drift_detection(false);
+
+    // Act
+    client.vehicles::<String, String>(&[]).expect("failed to get vehicles");
+    disabled.vehicles::<String, String>(&[]).expect("failed to get vehicles");
+
+    // Assert
+    let drift = client.schema_drift();
+    assert_eq!(drift.models().collect::<Vec<_>>(), vec!["vehicle"]);
+    assert_eq!(
+        drift.fields("vehicle").collect::<Vec<_>>(),
+        vec!["attributes.carriages.carriage_sequence", "relationships.occupancy"]
+    );
+    assert!(disabled.schema_drift().is_empty());
+}
+
+#[rstest]
+fn test_schema_drift_detection_lenient() {
+    // Arrange
+    let body = "{\"data\": [{\"type\": \"shape\", \"id\": \"a\", \"attributes\": {\"polyline\": \"abc\", \"color\": \"red\"}}, {\"type\": \"shape\", \"id\": \"b\", \"attributes\": {}}], \"jsonapi\": {\"version\": \"1.0\"}, \"included\": [{\"type\": \"route\", \"id\": \"Red\", \"attributes\": {}}, {\"type\": \"shape\", \"id\": \"c\", \"attributes\": {\"polyline\": \"def\", \"width\": 3}}]}";
+    let transport = InMemoryTransport::new(vec![(200, body)]);
+    let client = Client::without_key()
+        .with_transport(transport)
+        .with_lenient_decoding(true)
+        .with_schema_drift_detection(true);
+
+    // Act
+    let shapes = client.shapes::<String, String>(&[]).expect("failed to get shapes");
+
+    // Assert
+    let drift = client.schema_drift();
+    assert_eq!(shapes.meta.item_errors.len(), 2);
+    assert_eq!(drift.models().collect::<Vec<_>>(), vec!["shape"]);
+    assert_eq!(drift.fields("shape").collect::<Vec<_>>(), vec!["attributes.color", "attributes.width"]);
+}
@@ -45,7 +45,7 @@ macro_rules! test_endpoint_plural_with_route {
                 let error = client.$plural_func(&params).expect_err(&format!("{} did not fail", stringify!($plural_func)));
 
                 // Assert
-                if let ClientError::ResponseError { errors } = error {
+                if let ClientError::BadRequest { errors } = error {
                     assert_eq!(errors.errors.len(), 1);
                 } else {
                     panic!("wrong error type");
@@ -119,7 +119,7 @@ macro_rules! test_endpoint_singular_with_route {
                 let error = client.$singular_func("foobar").expect_err(&format!("{} did not fail", stringify!($singular_func)));
 
                 // Assert
-                if let ClientError::ResponseError { errors } = error {
+                if let ClientError::NotFound { errors } = error {
                     assert_eq!(errors.errors.len(), 1);
                 } else {
                     panic!("wrong error type");